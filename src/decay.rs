@@ -0,0 +1,90 @@
+//! Bernoulli trials with decaying success probability.
+
+use crate::Pcg32;
+
+/// A Bernoulli trial whose success probability decays geometrically with every sample.
+///
+/// This is useful to simulate diminishing event rates, such as the chance of a rare drop that
+/// shrinks each time it is rolled.
+///
+/// ```rust
+/// let mut g = pcg32::Pcg32::default();
+/// let mut b = pcg32::DecayingBool::new(1.0, 0.5);
+///
+/// assert!(b.sample(&mut g));
+/// assert_eq!(b.probability(), 0.5);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecayingBool {
+    p: f64,
+    decay: f64,
+}
+
+impl DecayingBool {
+    /// Creates a trial that succeeds with probability `p0` at first, after which the probability
+    /// is multiplied by `decay` on each call to [`sample`](Self::sample).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p0` or `decay` is not in `[0, 1]`.
+    pub fn new(p0: f64, decay: f64) -> Self {
+        assert!((0.0..=1.0).contains(&p0), "p0 must be in [0, 1]");
+        assert!((0.0..=1.0).contains(&decay), "decay must be in [0, 1]");
+        Self { p: p0, decay }
+    }
+
+    /// Returns `true` with the current probability and then decays the probability.
+    pub fn sample(&mut self, rng: &mut Pcg32) -> bool {
+        let result = rng.next_f64() < self.p;
+        self.p *= self.decay;
+        result
+    }
+
+    /// Returns the success probability of the next call to [`sample`](Self::sample).
+    pub fn probability(&self) -> f64 {
+        self.p
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecayingBool;
+    use crate::Pcg32;
+
+    /// Tests that the success rate of `DecayingBool` decays with each call.
+    #[test]
+    fn decaying_bool() {
+        let mut g = Pcg32::new(0x428a_2f98_d728_ae22, 0x7137_4491_23ef_65cd);
+        let (p0, decay) = (0.8, 0.5);
+        let n = 20_000;
+        let mut hits = [0u32; 6];
+        for _ in 0..n {
+            let mut b = DecayingBool::new(p0, decay);
+            for h in hits.iter_mut() {
+                *h += b.sample(&mut g) as u32;
+            }
+        }
+        let mut expected = p0;
+        for w in hits.windows(2) {
+            assert!(w[0] > w[1]);
+        }
+        for h in hits {
+            assert!((h as f64 / n as f64 - expected).abs() < 0.01);
+            expected *= decay;
+        }
+    }
+
+    /// Tests that `DecayingBool` is deterministic for a fixed seed.
+    #[test]
+    fn decaying_bool_deterministic() {
+        let mut g = Pcg32::new(0xb5c0_fbcf_ec4d_3b2f, 0xe9b5_dba5_8189_dbbc);
+        let mut h = g.clone();
+        let mut a = DecayingBool::new(0.9, 0.99);
+        let mut b = a.clone();
+        for _ in 0..1000 {
+            assert_eq!(a.sample(&mut g), b.sample(&mut h));
+        }
+        assert_eq!(a, b);
+        assert_eq!(g, h);
+    }
+}
@@ -0,0 +1,1018 @@
+//! Samplers for non-uniform distributions.
+
+use crate::Pcg32;
+
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+/// Cumulative distribution function of the standard normal distribution scaled by `2^32`, tabulated
+/// at `z = -4.0, -3.9, ..., 4.0`.
+const NORMAL_CDF: [u32; 81] = [
+    136027, 206572, 310732, 462996, 683366, 999134, 1447100, 2076291, 2951235, 4155824, 5797768,
+    8013607, 10974201, 14890539, 20019650, 26670309, 35208149, 46059702, 59714852, 76727102,
+    97711073, 123336685, 154319546, 191407205, 235361166, 286934745, 346847210, 415754916,
+    494220470, 582681295, 681419127, 790532219, 909912008, 1039225973, 1177908172, 1325158638,
+    1479952351, 1641057946, 1807065788, 1976424389, 2147483648, 2318542907, 2487901508, 2653909350,
+    2815014945, 2969808658, 3117059124, 3255741323, 3385055288, 3504435077, 3613548169, 3712286001,
+    3800746826, 3879212380, 3948120086, 4008032551, 4059606130, 4103560091, 4140647750, 4171630611,
+    4197256223, 4218240194, 4235252444, 4248907594, 4259759147, 4268296987, 4274947646, 4280076757,
+    4283993095, 4286953689, 4289169528, 4290811472, 4292016061, 4292891005, 4293520196, 4293968162,
+    4294283930, 4294504300, 4294656564, 4294760724, 4294831269,
+];
+
+/// Largest number of dice for which [`Pcg32::roll_sum_fast`] rolls each die directly.
+const ROLL_SUM_DIRECT_MAX: u32 = 64;
+
+/// Largest `r` for which [`Pcg32::next_negative_binomial`] sums geometric deviates directly.
+#[cfg(feature = "std")]
+const NEGATIVE_BINOMIAL_DIRECT_MAX: u64 = 16;
+
+impl Pcg32 {
+    /// Returns the number of consecutive zero bits before the first one bit in the output
+    /// stream, which follows the geometric distribution with success probability 0.5.
+    ///
+    /// The bits of each output word are scanned from the least significant bit. An all-zero word
+    /// continues the run into the next word, and the remaining bits of the word containing the
+    /// first one bit are discarded.
+    pub fn gen_run_length(&mut self) -> u32 {
+        let mut run = 0;
+        loop {
+            let word = self.generate();
+            if word != 0 {
+                return run + word.trailing_zeros();
+            }
+            run += 32;
+        }
+    }
+
+    /// Generates outputs until one falls below `threshold` and returns the number of outputs
+    /// consumed, including the successful one.
+    ///
+    /// The result follows the geometric distribution on `1, 2, ...` with success probability
+    /// `threshold / 2^32`, and thus has mean `2^32 / threshold`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is zero, which would never succeed.
+    pub fn hitting_time(&mut self, threshold: u32) -> u64 {
+        assert!(threshold > 0, "threshold must be nonzero");
+        let mut draws = 1;
+        while self.generate() >= threshold {
+            draws += 1;
+        }
+        draws
+    }
+
+    /// Returns the number of failures before the first success in Bernoulli trials with success
+    /// probability `p`, clamped to at most `cap`.
+    ///
+    /// The result equals `min(X, cap)` for a geometric deviate `X` on `0, 1, ...`, i.e., the
+    /// probability mass beyond `cap` is piled up at `cap` rather than renormalized, which suits
+    /// bounded retry counts. A zero `p` returns `cap` without drawing anything.
+    ///
+    /// With the `std` feature, `X` is drawn by inversion from a single uniform in `O(1)` time.
+    /// Without it, the trials are simulated one by one and stop at `cap`, which takes `O(cap)`
+    /// time, so the two builds produce different sequences from the same state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in `[0, 1]`.
+    pub fn next_geometric_capped(&mut self, p: f64, cap: u64) -> u64 {
+        assert!((0.0..=1.0).contains(&p), "p must be in [0, 1]");
+        if p == 0.0 {
+            return cap;
+        }
+
+        #[cfg(feature = "std")]
+        {
+            self.next_geometric(p).min(cap)
+        }
+
+        #[cfg(not(feature = "std"))]
+        {
+            let mut failures = 0;
+            while failures < cap && self.next_f64() >= p {
+                failures += 1;
+            }
+            failures
+        }
+    }
+
+    /// Performs up to `max` Bernoulli trials with success probability `p` and returns the
+    /// zero-based index of the first success, or `None` if all of them fail.
+    ///
+    /// This is a bounded geometric search: unlike
+    /// [`next_geometric_capped`](Self::next_geometric_capped), running out of trials is reported
+    /// separately from a success at the last trial. The trials are simulated one by one, so this
+    /// consumes at most `max` uniforms.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in `[0, 1]`.
+    pub fn first_success_index(&mut self, p: f64, max: usize) -> Option<usize> {
+        assert!((0.0..=1.0).contains(&p), "p must be in [0, 1]");
+        (0..max).find(|_| self.next_f64() < p)
+    }
+
+    /// Returns the number of successes in `draws` samples drawn without replacement from a
+    /// population of `population` items containing `successes` successes, following the
+    /// hypergeometric distribution.
+    ///
+    /// The roles of successes and failures and of drawn and undrawn items are first swapped as
+    /// needed so that both the counted class and the sample are at most half the population.
+    /// The sample is then drawn in constant expected time regardless of the population size:
+    /// by sequential inversion of the cumulative distribution if the mode is below 10, or
+    /// otherwise by the H2PE rejection algorithm of Kachitvichyanukul and Schmeiser,
+    /// "Computer Generation of Hypergeometric Random Variates," J. Stat. Comput. Simul. (1985),
+    /// evaluating the acceptance test with Stirling's series instead of the squeeze steps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `successes` or `draws` is greater than `population`.
+    #[cfg(feature = "std")]
+    pub fn next_hypergeometric(&mut self, population: u64, successes: u64, draws: u64) -> u64 {
+        assert!(
+            successes <= population,
+            "successes must not exceed population"
+        );
+        assert!(draws <= population, "draws must not exceed population");
+        let count_failures = successes > population - successes;
+        let n1 = if count_failures {
+            population - successes
+        } else {
+            successes
+        };
+        let count_undrawn = draws > population - draws;
+        let k = if count_undrawn {
+            population - draws
+        } else {
+            draws
+        };
+
+        let x = self.hypergeometric_minority(population, n1, k);
+        let x = if count_undrawn { n1 - x } else { x };
+        if count_failures {
+            draws - x
+        } else {
+            x
+        }
+    }
+
+    /// Samples the number of items of a class of `n1` items among `k` items drawn without
+    /// replacement from `population` items, assuming `n1` and `k` are at most half the
+    /// population.
+    #[cfg(feature = "std")]
+    fn hypergeometric_minority(&mut self, population: u64, n1: u64, k: u64) -> u64 {
+        if n1 == 0 || k == 0 {
+            return 0;
+        }
+        // the other class, less the sample size, which is non-negative by assumption
+        let rest = population - n1 - k;
+        let max = n1.min(k);
+        let mode = ((k as f64 + 1.0) * (n1 as f64 + 1.0) / (population as f64 + 2.0)) as u64;
+
+        if mode < 10 {
+            // `P(X = 0) = C(population - n1, k) / C(population, k)`
+            let p0 = (ln_factorial_delta(population as f64, -(n1 as f64))
+                - ln_factorial_delta((population - k) as f64, -(n1 as f64)))
+            .exp();
+            'retry: loop {
+                let (mut u, mut p, mut x) = (self.next_f64(), p0, 0);
+                while u >= p {
+                    // start over if rounding errors leave `u` beyond the whole mass
+                    if x == max || p == 0.0 {
+                        continue 'retry;
+                    }
+                    u -= p;
+                    p *= (n1 - x) as f64 * (k - x) as f64;
+                    p /= (x + 1) as f64 * (rest + x + 1) as f64;
+                    x += 1;
+                }
+                return x;
+            }
+        }
+
+        // `ln(f(mode + delta) / f(mode))`, where `f(x) ∝ 1 / (x! (n1 - x)! (k - x)! (rest + x)!)`
+        let ln_ratio = |delta: f64| {
+            -(ln_factorial_delta(mode as f64, delta)
+                + ln_factorial_delta((n1 - mode) as f64, -delta)
+                + ln_factorial_delta((k - mode) as f64, -delta)
+                + ln_factorial_delta((rest + mode) as f64, delta))
+        };
+        let (m, n1f, kf, restf) = (mode as f64, n1 as f64, k as f64, rest as f64);
+        let pop = population as f64;
+        let var = kf * (n1f / pop) * ((pop - n1f) / pop) * ((pop - kf) / (pop - 1.0));
+        let d = (1.5 * var.sqrt() + 0.5).floor();
+
+        // the hat is flat over `x_l..x_r` and decays exponentially beyond both ends
+        let (x_l, x_r) = (m - d + 0.5, m + d + 0.5);
+        let k_l = ln_ratio(-d + 0.5).exp();
+        let k_r = ln_ratio(d - 0.5).exp();
+        let lambda_l = -(x_l * (restf + x_l) / ((n1f - x_l + 1.0) * (kf - x_l + 1.0))).ln();
+        let lambda_r = -((n1f - x_r + 1.0) * (kf - x_r + 1.0) / (x_r * (restf + x_r))).ln();
+        let p1 = 2.0 * d;
+        let p2 = p1 + k_l / lambda_l;
+        let p3 = p2 + k_r / lambda_r;
+
+        loop {
+            let u = self.next_f64() * p3;
+            let v = self.next_f64_nonzero();
+            // offset from the mode and the height under the hat
+            let (delta, v) = if u < p1 {
+                ((-d + 0.5 + u).floor(), v)
+            } else if u < p2 {
+                (
+                    (-d + 0.5 + v.ln() / lambda_l).floor(),
+                    v * (u - p1) * lambda_l,
+                )
+            } else {
+                (
+                    (d + 0.5 - v.ln() / lambda_r).floor(),
+                    v * (u - p2) * lambda_r,
+                )
+            };
+            if delta < -m || delta > (max - mode) as f64 {
+                continue;
+            }
+            if v.ln() <= ln_ratio(delta) {
+                return mode.wrapping_add_signed(delta as i64);
+            }
+        }
+    }
+
+    /// Returns the sum of `count` rolls of a fair die with `sides` faces.
+    ///
+    /// Up to 64 dice are rolled one by one, so the result follows the exact distribution. Beyond
+    /// that crossover point, the sum is instead drawn from the normal approximation of the
+    /// dice-sum distribution by inverting a precomputed cumulative table with a single output, so
+    /// the cost no longer grows with `count`. The approximation is rounded to the nearest
+    /// integer, clamped to `count..=count * sides`, and truncated at four standard deviations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sides` is zero or if `count * sides` overflows `u32`.
+    pub fn roll_sum_fast(&mut self, count: u32, sides: u32) -> u32 {
+        assert!(sides > 0, "sides must be nonzero");
+        let max = count
+            .checked_mul(sides)
+            .expect("count * sides must fit in u32");
+        if count <= ROLL_SUM_DIRECT_MAX {
+            return (0..count).map(|_| 1 + self.bounded_u32(sides)).sum();
+        }
+
+        let u = self.generate();
+        let i = NORMAL_CDF.partition_point(|&p| p <= u);
+        let z = match i {
+            0 => -4.0,
+            81 => 4.0,
+            _ => {
+                let (lo, hi) = (NORMAL_CDF[i - 1], NORMAL_CDF[i]);
+                (i - 1) as f64 * 0.1 - 4.0 + 0.1 * (u - lo) as f64 / (hi - lo) as f64
+            }
+        };
+
+        // variance count * (sides^2 - 1) / 12 scaled by 2^32 to keep 16 fractional bits in sqrt
+        let sides = sides as u128;
+        let var = ((count as u128 * (sides * sides - 1)) << 32) / 12;
+        let std_dev = var.isqrt() as f64 / 65536.0;
+        let mean = count as f64 * (sides as f64 + 1.0) / 2.0;
+        ((mean + z * std_dev + 0.5) as u32).clamp(count, max)
+    }
+
+    /// Generates a standard normal deviate using the Box–Muller transform.
+    #[cfg(feature = "std")]
+    pub(crate) fn next_standard_normal(&mut self) -> f64 {
+        let u = 1.0 - self.next_f64(); // in (0, 1] to keep `ln` finite
+        let v = self.next_f64();
+        (-2.0 * u.ln()).sqrt() * (core::f64::consts::TAU * v).cos()
+    }
+
+    /// Generates a strictly positive standard exponential deviate by inversion.
+    ///
+    /// Without the `std` feature, the logarithm is computed by `ln_portable`, which may differ
+    /// from the standard library in the last bit.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn next_exponential(&mut self) -> f64 {
+        let u = self.next_f64_nonzero();
+
+        #[cfg(feature = "std")]
+        {
+            -u.ln()
+        }
+
+        #[cfg(not(feature = "std"))]
+        {
+            -ln_portable(u)
+        }
+    }
+
+    /// Generates the number of failures before the first success in Bernoulli trials with
+    /// success probability `p` in `(0, 1]`, by inversion.
+    #[cfg(feature = "std")]
+    fn next_geometric(&mut self, p: f64) -> u64 {
+        if p == 1.0 {
+            return 0;
+        }
+        let u = 1.0 - self.next_f64(); // in (0, 1] to keep `ln` finite
+        (u.ln() / (-p).ln_1p()).floor() as u64
+    }
+
+    /// Generates a normal deviate with the specified mean and standard deviation.
+    #[cfg(feature = "std")]
+    pub(crate) fn next_normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+        mean + std_dev * self.next_standard_normal()
+    }
+
+    /// Generates a sample from a mixture of two normal distributions.
+    ///
+    /// The first component, with mean `m1` and standard deviation `s1`, is chosen with
+    /// probability `weight`; otherwise the sample is drawn from the second component with mean
+    /// `m2` and standard deviation `s2`. This is handy for modeling bimodal data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is not in `[0, 1]`.
+    #[cfg(feature = "std")]
+    pub fn next_mixture_normal(&mut self, weight: f64, m1: f64, s1: f64, m2: f64, s2: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&weight), "weight must be in [0, 1]");
+        if self.next_f64() < weight {
+            self.next_normal(m1, s1)
+        } else {
+            self.next_normal(m2, s2)
+        }
+    }
+
+    /// Generates a sample from an arbitrary continuous distribution by applying its inverse
+    /// cumulative distribution function `inv_cdf` to a uniform draw in `[0, 1)`.
+    ///
+    /// For example, `g.next_inverse_cdf(|u| -(1.0 - u).ln())` samples the standard exponential
+    /// distribution.
+    #[cfg(feature = "std")]
+    pub fn next_inverse_cdf<F: Fn(f64) -> f64>(&mut self, inv_cdf: F) -> f64 {
+        inv_cdf(self.next_f64())
+    }
+
+    /// Computes one Euler–Maruyama step of the Ornstein–Uhlenbeck process
+    /// `dx = theta * (mu - x) * dt + sigma * dW`, returning the value that follows `prev`.
+    ///
+    /// Iterating this method yields mean-reverting noise that drifts toward `mu` at rate
+    /// `theta`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dt` is negative.
+    #[cfg(feature = "std")]
+    pub fn ou_step(&mut self, prev: f64, theta: f64, mu: f64, sigma: f64, dt: f64) -> f64 {
+        assert!(dt >= 0.0, "dt must be non-negative");
+        prev + theta * (mu - prev) * dt + sigma * dt.sqrt() * self.next_standard_normal()
+    }
+
+    /// Generates a series of `n` values following the first-order autoregressive process
+    /// `x[t] = phi * x[t - 1] + e[t]`, where `e[t]` is normal noise with standard deviation
+    /// `sigma` and `x[-1] = 0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `|phi| >= 1`, for which the process is not stationary.
+    #[cfg(feature = "std")]
+    pub fn ar1_series(&mut self, phi: f64, sigma: f64, n: usize) -> Vec<f64> {
+        assert!(phi.abs() < 1.0, "phi must satisfy |phi| < 1");
+        let mut x = 0.0;
+        (0..n)
+            .map(|_| {
+                x = phi * x + self.next_normal(0.0, sigma);
+                x
+            })
+            .collect()
+    }
+
+    /// Generates the number of failures before the `r`-th success in Bernoulli trials with
+    /// success probability `p`, following the negative binomial distribution.
+    ///
+    /// Up to 16 successes, the result is computed as the sum of `r` geometric deviates. Beyond
+    /// that, it is drawn from the gamma–Poisson mixture `Poisson(Gamma(r, (1 - p) / p))`, so the
+    /// cost no longer grows with `r`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in `(0, 1]`.
+    #[cfg(feature = "std")]
+    pub fn next_negative_binomial(&mut self, r: u64, p: f64) -> u64 {
+        assert!(p > 0.0 && p <= 1.0, "p must be in (0, 1]");
+        if r <= NEGATIVE_BINOMIAL_DIRECT_MAX {
+            (0..r).map(|_| self.next_geometric(p)).sum()
+        } else {
+            let mean = (1.0 - p) / p * self.next_gamma(r as f64);
+            self.next_poisson(mean)
+        }
+    }
+
+    /// Adds independent normal noise with mean zero and standard deviation `sigma` to each
+    /// element of `data` in place.
+    ///
+    /// This is a building block of data augmentation and of tests for noise-robust algorithms.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sigma` is negative or not finite.
+    #[cfg(feature = "std")]
+    pub fn add_noise(&mut self, data: &mut [f64], sigma: f64) {
+        assert!(
+            sigma.is_finite() && sigma >= 0.0,
+            "sigma must be finite and non-negative"
+        );
+        for x in data {
+            *x += self.next_normal(0.0, sigma);
+        }
+    }
+
+    /// Generates a sample from the gamma distribution with the specified `shape` and unit scale
+    /// by the method of Marsaglia and Tsang.
+    ///
+    /// A shape below one is boosted to `shape + 1` and the sample is scaled by `u^(1 / shape)`.
+    #[cfg(feature = "std")]
+    fn next_gamma(&mut self, shape: f64) -> f64 {
+        if shape < 1.0 {
+            let u = 1.0 - self.next_f64();
+            return self.next_gamma(shape + 1.0) * u.powf(1.0 / shape);
+        }
+        let d = shape - 1.0 / 3.0;
+        let c = 1.0 / (9.0 * d).sqrt();
+        loop {
+            let z = self.next_standard_normal();
+            let x = c * z;
+            if x <= -1.0 {
+                continue;
+            }
+            // `d - d * v + d * ln(v)` with `v = (1 + x)^3`, expanded so that the terms of order
+            // `d` cancel analytically rather than in floating point when `d` is huge
+            let u = 1.0 - self.next_f64();
+            if u.ln() < 0.5 * z * z + d * (3.0 * x.ln_1p() - x * (3.0 + x * (3.0 + x))) {
+                return d * (1.0 + x).powi(3);
+            }
+        }
+    }
+
+    /// Generates a sample from the Poisson distribution with the specified `mean`.
+    ///
+    /// A mean below 10 is sampled by sequential inversion. A larger mean is sampled in constant
+    /// expected time by the transformed rejection method with squeeze (PTRS) of Hörmann.
+    #[cfg(feature = "std")]
+    fn next_poisson(&mut self, mean: f64) -> u64 {
+        if mean < 10.0 {
+            let p0 = (-mean).exp();
+            'retry: loop {
+                let (mut u, mut p, mut x) = (self.next_f64(), p0, 0);
+                while u >= p {
+                    // start over if rounding errors leave `u` beyond the whole mass
+                    if p == 0.0 {
+                        continue 'retry;
+                    }
+                    u -= p;
+                    x += 1;
+                    p *= mean / x as f64;
+                }
+                return x;
+            }
+        }
+
+        let smu = mean.sqrt();
+        let b = 0.931 + 2.53 * smu;
+        let a = -0.059 + 0.02483 * b;
+        let inv_alpha = 1.1239 + 1.1328 / (b - 3.4);
+        let vr = 0.9277 - 3.6224 / (b - 2.0);
+        loop {
+            let u = self.next_f64() - 0.5;
+            let v = self.next_f64();
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * a / us + b) * u + mean + 0.43).floor();
+            if us >= 0.07 && v <= vr {
+                return k as u64;
+            }
+            if k < 0.0 || (us < 0.013 && v > us) {
+                continue;
+            }
+            // `ln(mean^k e^-mean / k!)`, with Stirling's series expanded around the mean for a
+            // large `k` to avoid subtracting huge logarithms
+            let ln_pmf = if k < 32.0 {
+                k * mean.ln() - mean - ln_factorial(k)
+            } else {
+                let delta = k - mean;
+                delta
+                    - k * (delta / mean).ln_1p()
+                    - 0.5 * (core::f64::consts::TAU * k).ln()
+                    - stirling_tail(k)
+            };
+            if v.ln() + inv_alpha.ln() - (a / (us * us) + b).ln() <= ln_pmf {
+                return k as u64;
+            }
+        }
+    }
+
+    /// Returns `true` with a probability that is itself drawn from the beta distribution
+    /// `Beta(alpha, beta)`.
+    ///
+    /// The probability is sampled as `x / (x + y)` from two gamma deviates with shapes `alpha`
+    /// and `beta`, and then a coin is flipped with that probability. Marginally the result is
+    /// `true` with probability `alpha / (alpha + beta)`, but the extra draw models uncertainty
+    /// about the rate, as in hierarchical Bayesian simulations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` or `beta` is not positive and finite.
+    #[cfg(feature = "std")]
+    pub fn gen_bool_beta(&mut self, alpha: f64, beta: f64) -> bool {
+        assert!(
+            alpha.is_finite() && alpha > 0.0 && beta.is_finite() && beta > 0.0,
+            "alpha and beta must be positive and finite"
+        );
+        let x = self.next_gamma(alpha);
+        let y = self.next_gamma(beta);
+        // both deviates may underflow to zero for tiny shapes; fall back to the mean
+        let p = if x + y > 0.0 {
+            x / (x + y)
+        } else {
+            alpha / (alpha + beta)
+        };
+        self.next_f64() < p
+    }
+}
+
+/// Computes the natural logarithm of a positive normal `x` without the standard library.
+///
+/// `x` is split into `m * 2^e` with `m` in `[sqrt(1/2), sqrt(2))`, and `ln(m)` is summed from the
+/// series `2 * atanh(s) = 2 * (s + s^3 / 3 + s^5 / 5 + ...)` with `s = (m - 1) / (m + 1)`, where
+/// `|s| < 0.172` makes twelve terms enough for double precision.
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+fn ln_portable(x: f64) -> f64 {
+    debug_assert!(x.is_normal() && x > 0.0);
+    let bits = x.to_bits();
+    let mut e = (bits >> 52) as i32 - 1023;
+    let mut m = f64::from_bits(bits & ((1 << 52) - 1) | 1023 << 52);
+    if m > core::f64::consts::SQRT_2 {
+        m /= 2.0;
+        e += 1;
+    }
+    let s = (m - 1.0) / (m + 1.0);
+    let z = s * s;
+    let mut sum = 0.0;
+    for k in (0..12).rev() {
+        sum = sum * z + 1.0 / (2 * k + 1) as f64;
+    }
+    2.0 * s * sum + e as f64 * core::f64::consts::LN_2
+}
+
+/// Computes `ln(v!)`, generalized to a real `v >= 0` through the gamma function.
+///
+/// Small arguments are shifted up to 32 by the recurrence `v! = (v + 1)! / (v + 1)` before
+/// Stirling's series is applied, which keeps the absolute error below `1e-13`.
+#[cfg(feature = "std")]
+fn ln_factorial(v: f64) -> f64 {
+    let (mut w, mut shift) = (v, 1.0);
+    while w < 32.0 {
+        w += 1.0;
+        shift *= w;
+    }
+    w * w.ln() - w + 0.5 * (core::f64::consts::TAU * w).ln() + stirling_tail(w) - shift.ln()
+}
+
+/// Computes `ln((q + delta)!) - ln(q!)` without the cancellation of subtracting two huge
+/// logarithms when `delta` is small relative to `q`.
+#[cfg(feature = "std")]
+fn ln_factorial_delta(q: f64, delta: f64) -> f64 {
+    let p = q + delta;
+    if delta == 0.0 {
+        0.0
+    } else if p.min(q) < 32.0 {
+        ln_factorial(p) - ln_factorial(q)
+    } else {
+        delta * p.ln() + (q + 0.5) * (delta / q).ln_1p() - delta + stirling_tail(p)
+            - stirling_tail(q)
+    }
+}
+
+/// Returns the correction terms of Stirling's series for `ln(w!)` beyond
+/// `w ln(w) - w + ln(2πw) / 2`.
+#[cfg(feature = "std")]
+fn stirling_tail(w: f64) -> f64 {
+    let r = 1.0 / (w * w);
+    (1.0 / 12.0 - r * (1.0 / 360.0 - r / 1260.0)) / w
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    /// Tests that `gen_run_length` follows the geometric distribution with `p = 0.5`.
+    #[test]
+    fn gen_run_length() {
+        let mut g = Pcg32::new(0x243f_6a88_85a3_08d3, 0x1319_8a2e_0370_7344);
+        let n = 100_000;
+        let mut hist = [0u32; 8];
+        let mut sum = 0;
+        for _ in 0..n {
+            let run = g.gen_run_length();
+            sum += run as u64;
+            if let Some(h) = hist.get_mut(run as usize) {
+                *h += 1;
+            }
+        }
+        assert!((sum as f64 / n as f64 - 1.0).abs() < 0.02);
+        for (k, &h) in hist.iter().enumerate() {
+            let expected = 0.5f64.powi(k as i32 + 1);
+            assert!((h as f64 / n as f64 - expected).abs() < 0.01);
+        }
+
+        // an all-zero word continues the run into the next word
+        let mut g = Pcg32 { state: 0, inc: 1 };
+        let mut h = g.clone();
+        h.generate();
+        assert_eq!(g.gen_run_length(), 32 + h.gen_run_length());
+    }
+
+    /// Tests that the mean of `hitting_time` approximates `2^32 / threshold`.
+    #[test]
+    fn hitting_time() {
+        let mut g = Pcg32::new(0x4ed8_aa4a_5b9c_ca4f, 0x682e_6ff3_748f_82ee);
+        for threshold in [u32::MAX, 1 << 31, 1 << 29, 123_456_789] {
+            let n = 100_000;
+            let sum: u64 = (0..n).map(|_| g.hitting_time(threshold)).sum();
+            let expected = (1u64 << 32) as f64 / threshold as f64;
+            assert!((sum as f64 / n as f64 - expected).abs() < 0.02 * expected);
+        }
+    }
+
+    /// Tests that `hitting_time` rejects a zero threshold.
+    #[test]
+    #[should_panic]
+    fn hitting_time_zero_threshold() {
+        Pcg32::default().hitting_time(0);
+    }
+
+    /// Tests that `next_geometric_capped` never exceeds the cap and follows the clamped law.
+    #[test]
+    fn next_geometric_capped() {
+        let mut g = Pcg32::new(0x72be_5d74_f27b_896f, 0x80de_b1fe_3b16_96b1);
+        for (p, cap) in [(0.5, 3), (0.2, 10), (0.05, 5), (1.0, 4), (0.3, 0)] {
+            let n = 100_000;
+            let mut sum = 0;
+            let mut at_cap = 0;
+            for _ in 0..n {
+                let x = g.next_geometric_capped(p, cap);
+                assert!(x <= cap);
+                sum += x;
+                at_cap += (x == cap) as u32;
+            }
+            let mean = sum as f64 / n as f64;
+            let uncapped_mean = (1.0 - p) / p;
+            assert!(mean <= uncapped_mean);
+
+            // `P(X >= cap) = (1 - p)^cap`
+            let expected = (1.0 - p).powi(cap as i32);
+            assert!((at_cap as f64 / n as f64 - expected).abs() < 0.01);
+        }
+
+        assert_eq!(g.next_geometric_capped(0.0, 7), 7);
+        assert_eq!(g.next_geometric_capped(0.0, u64::MAX), u64::MAX);
+    }
+
+    /// Tests that `ln_portable` agrees with the standard library logarithm.
+    #[cfg(all(feature = "alloc", not(feature = "std")))]
+    #[test]
+    fn ln_portable() {
+        let mut g = Pcg32::new(0x2b99_2ddf_a232_49d6, 0x5d4c_7f5e_1a6b_d4c3);
+        for _ in 0..100_000 {
+            let x = g.next_f64_nonzero();
+            let (got, expected) = (super::ln_portable(x), x.ln());
+            assert!((got - expected).abs() <= 2.0 * f64::EPSILON * expected.abs());
+        }
+        for x in [
+            f64::EPSILON / 2.0,
+            0.5,
+            1.0 - f64::EPSILON / 2.0,
+            1.0,
+            2.0,
+            1e300,
+        ] {
+            assert!((super::ln_portable(x) - x.ln()).abs() <= 2.0 * f64::EPSILON * x.ln().abs());
+        }
+    }
+
+    /// Tests that `next_geometric_capped` returns promptly with a huge cap.
+    #[cfg(feature = "std")]
+    #[test]
+    fn next_geometric_capped_huge_cap() {
+        let mut g = Pcg32::new(0x1c6e_f372_fe94_f82b, 0x6a09_e667_f3bc_c908);
+        let n = 100_000;
+        let sum: f64 = (0..n)
+            .map(|_| g.next_geometric_capped(1e-12, u64::MAX) as f64)
+            .sum();
+        let expected = (1.0 - 1e-12) / 1e-12;
+        assert!((sum / n as f64 - expected).abs() < 0.02 * expected);
+    }
+
+    /// Tests that `first_success_index` handles certain outcomes and follows the truncated
+    /// geometric distribution.
+    #[test]
+    fn first_success_index() {
+        let mut g = Pcg32::new(0x1d2c_3b4a_5968_7f0e, 0x9a8b_7c6d_5e4f_3021);
+        for max in [1, 2, 10, 1000] {
+            for _ in 0..100 {
+                assert_eq!(g.first_success_index(1.0, max), Some(0));
+                assert_eq!(g.first_success_index(0.0, max), None);
+            }
+        }
+        assert_eq!(g.first_success_index(1.0, 0), None);
+
+        let (p, max, n) = (0.2, 5, 100_000);
+        let mut hist = [0u32; 6];
+        for _ in 0..n {
+            let i = g.first_success_index(p, max);
+            hist[i.unwrap_or(max)] += 1;
+        }
+        for (i, &count) in hist.iter().enumerate() {
+            let expected = (1.0 - p).powi(i as i32) * if i < max { p } else { 1.0 };
+            assert!((count as f64 / n as f64 - expected).abs() < 0.01);
+        }
+    }
+
+    /// Tests that the sample mean and variance of `next_hypergeometric` match the theory.
+    #[cfg(feature = "std")]
+    #[test]
+    fn next_hypergeometric() {
+        let mut g = Pcg32::new(0x4be2_9c71_0d58_a3f6, 0xe01b_7d34_95c6_2a8f);
+        let cases = [
+            (50, 20, 10),
+            (50, 20, 40),
+            (100, 3, 97),
+            (1000, 900, 30),
+            (10, 10, 4),
+            (1000, 400, 300),
+            (5000, 2500, 4900),
+        ];
+        for (population, successes, draws) in cases {
+            let n = 50_000;
+            let (mut sum, mut sum_sq) = (0.0, 0.0);
+            for _ in 0..n {
+                let x = g.next_hypergeometric(population, successes, draws);
+                assert!(x <= successes.min(draws));
+                assert!(x + (population - successes) >= draws);
+                sum += x as f64;
+                sum_sq += (x * x) as f64;
+            }
+            let (pop, k, d) = (population as f64, successes as f64, draws as f64);
+            let mean = d * k / pop;
+            let var = mean * (1.0 - k / pop) * (pop - d) / (pop - 1.0);
+            assert!((sum / n as f64 - mean).abs() < 0.01 * mean.max(1.0));
+            let sample_mean = sum / n as f64;
+            let sample_var = sum_sq / n as f64 - sample_mean * sample_mean;
+            assert!((sample_var - var).abs() < 0.03 * var.max(0.1));
+        }
+
+        assert_eq!(g.next_hypergeometric(0, 0, 0), 0);
+        assert_eq!(g.next_hypergeometric(30, 12, 0), 0);
+        assert_eq!(g.next_hypergeometric(30, 12, 30), 12);
+        assert_eq!(g.next_hypergeometric(30, 0, 17), 0);
+        assert_eq!(g.next_hypergeometric(30, 30, 17), 17);
+        assert_eq!(g.next_hypergeometric(u64::MAX, 1, u64::MAX), 1);
+    }
+
+    /// Tests that `next_hypergeometric` handles huge populations in constant expected time.
+    #[cfg(feature = "std")]
+    #[test]
+    fn next_hypergeometric_huge_population() {
+        let mut g = Pcg32::new(0x93c4_67e3_7db0_c7a4, 0xd1be_3f81_0152_cb56);
+        let cases = [
+            (1 << 62, 1 << 61, 1 << 60),
+            (1 << 62, 3, 1 << 61),
+            (u64::MAX, 1 << 40, 1 << 30),
+        ];
+        for (population, successes, draws) in cases {
+            let (pop, k, d) = (population as f64, successes as f64, draws as f64);
+            let mean = d * k / pop;
+            let var = mean * (1.0 - k / pop) * (pop - d) / (pop - 1.0);
+
+            // accumulate deviations from the mean to avoid cancellation in huge values
+            let n = 20_000;
+            let (mut sum, mut sum_sq) = (0.0, 0.0);
+            for _ in 0..n {
+                let x = g.next_hypergeometric(population, successes, draws) as f64 - mean;
+                sum += x;
+                sum_sq += x * x;
+            }
+            assert!((sum / n as f64).abs() < 5.0 * (var / n as f64).sqrt());
+            assert!((sum_sq / n as f64 / var - 1.0).abs() < 0.05);
+        }
+    }
+
+    /// Tests that `next_hypergeometric` rejects more draws than the population.
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic]
+    fn next_hypergeometric_too_many_draws() {
+        Pcg32::default().next_hypergeometric(10, 5, 11);
+    }
+
+    /// Tests that the fast path of `roll_sum_fast` matches the distribution of direct rolling.
+    #[test]
+    fn roll_sum_fast() {
+        let mut g = Pcg32::new(0x1319_8a2e_0370_7344, 0xa409_3822_299f_31d0);
+        let (count, sides) = (100, 6);
+        let n = 20_000;
+        let mut fast = [0u32; 601];
+        let mut direct = [0u32; 601];
+        for _ in 0..n {
+            let x = g.roll_sum_fast(count, sides);
+            assert!((count..=count * sides).contains(&x));
+            fast[x as usize] += 1;
+            let y: u32 = (0..count).map(|_| 1 + g.bounded_u32(sides)).sum();
+            direct[y as usize] += 1;
+        }
+
+        // two-sample Kolmogorov–Smirnov statistic
+        let (mut cdf_fast, mut cdf_direct, mut ks) = (0, 0, 0u32);
+        for (f, d) in fast.iter().zip(&direct) {
+            cdf_fast += f;
+            cdf_direct += d;
+            ks = ks.max(cdf_fast.abs_diff(cdf_direct));
+        }
+        assert!((ks as f64 / n as f64) < 0.02);
+
+        assert_eq!(g.roll_sum_fast(1000, 1), 1000);
+        assert_eq!(g.roll_sum_fast(0, 6), 0);
+        assert!((3..=18).contains(&g.roll_sum_fast(3, 6)));
+    }
+
+    /// Tests that `roll_sum_fast` rejects a sum that may overflow.
+    #[test]
+    #[should_panic]
+    fn roll_sum_fast_overflow() {
+        Pcg32::default().roll_sum_fast(1 << 16, 1 << 16);
+    }
+
+    /// Tests that the sample mean of `next_mixture_normal` approaches the mixture mean.
+    #[cfg(feature = "std")]
+    #[test]
+    fn next_mixture_normal() {
+        let mut g = Pcg32::new(0x9e37_79b9_7f4a_7c15, 0xbf58_476d_1ce4_e5b9);
+        for (weight, m1, s1, m2, s2) in [
+            (0.3, -2.0, 1.0, 5.0, 0.5),
+            (0.5, 0.0, 1.0, 10.0, 2.0),
+            (1.0, 3.0, 0.1, -3.0, 0.1),
+            (0.0, 3.0, 0.1, -3.0, 0.1),
+        ] {
+            let n = 100_000;
+            let sum: f64 = (0..n)
+                .map(|_| g.next_mixture_normal(weight, m1, s1, m2, s2))
+                .sum();
+            let expected = weight * m1 + (1.0 - weight) * m2;
+            assert!((sum / n as f64 - expected).abs() < 0.05);
+        }
+    }
+
+    /// Tests that `next_inverse_cdf` with the exponential inverse CDF matches `next_exponential`.
+    #[cfg(feature = "std")]
+    #[test]
+    fn next_inverse_cdf() {
+        let mut g = Pcg32::new(0x5cb0_a9dc_bd41_fbd4, 0x76f9_88da_8311_53b5);
+        let mut h = g.clone();
+        for _ in 0..10_000 {
+            assert_eq!(g.next_inverse_cdf(|u| -u.ln()), h.next_exponential());
+        }
+
+        // uniform on [2, 5) has mean 3.5
+        let n = 100_000;
+        let sum: f64 = (0..n).map(|_| g.next_inverse_cdf(|u| 2.0 + 3.0 * u)).sum();
+        assert!((sum / n as f64 - 3.5).abs() < 0.01);
+    }
+
+    /// Tests that the lag-1 autocorrelation of `ar1_series` approximates `phi`.
+    #[cfg(feature = "std")]
+    #[test]
+    fn ar1_series() {
+        let mut g = Pcg32::new(0xd807_aa98_a303_0242, 0x1283_5b01_4570_6fbe);
+        for phi in [-0.5, 0.0, 0.3, 0.9] {
+            let n = 100_000;
+            let xs = g.ar1_series(phi, 2.0, n);
+            assert_eq!(xs.len(), n);
+            let mean = xs.iter().sum::<f64>() / n as f64;
+            let var = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+            let cov = xs
+                .windows(2)
+                .map(|w| (w[0] - mean) * (w[1] - mean))
+                .sum::<f64>();
+            assert!((cov / var - phi).abs() < 0.02);
+        }
+        assert!(g.ar1_series(0.5, 1.0, 0).is_empty());
+    }
+
+    /// Tests that `ar1_series` rejects a non-stationary coefficient.
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic]
+    fn ar1_series_non_stationary() {
+        Pcg32::default().ar1_series(1.0, 1.0, 10);
+    }
+
+    /// Tests that a long run of `ou_step` reverts to the long-term mean.
+    #[cfg(feature = "std")]
+    #[test]
+    fn ou_step() {
+        let mut g = Pcg32::new(0xa458_fea3_f493_3d7e, 0x0d95_748f_728e_b658);
+        let (theta, mu, sigma, dt) = (1.0, 3.0, 0.5, 0.01);
+        let mut x = -10.0;
+        for _ in 0..1_000 {
+            x = g.ou_step(x, theta, mu, sigma, dt);
+        }
+        let n = 200_000;
+        let mut sum = 0.0;
+        for _ in 0..n {
+            x = g.ou_step(x, theta, mu, sigma, dt);
+            sum += x;
+        }
+        assert!((sum / n as f64 - mu).abs() < 0.05);
+    }
+
+    /// Tests that the sample mean of `next_negative_binomial` approaches `r * (1 - p) / p`.
+    #[cfg(feature = "std")]
+    #[test]
+    fn next_negative_binomial() {
+        let mut g = Pcg32::new(0xca27_3ece_ea26_619c, 0xd186_b8c7_21c0_c207);
+        for (r, p) in [(0, 0.5), (1, 0.5), (5, 0.3), (20, 0.8), (3, 1.0)] {
+            let n = 100_000;
+            let sum: u64 = (0..n).map(|_| g.next_negative_binomial(r, p)).sum();
+            let expected = r as f64 * (1.0 - p) / p;
+            assert!((sum as f64 / n as f64 - expected).abs() < 0.01 * expected.max(1.0));
+        }
+
+        // the gamma–Poisson mixture beyond the direct summation
+        for (r, p) in [(17, 0.5), (100, 0.3), (1000, 0.9)] {
+            let n = 100_000;
+            let (mut sum, mut sum_sq) = (0.0, 0.0);
+            for _ in 0..n {
+                let x = g.next_negative_binomial(r, p) as f64;
+                sum += x;
+                sum_sq += x * x;
+            }
+            let mean = r as f64 * (1.0 - p) / p;
+            let var = mean / p;
+            let sample_mean = sum / n as f64;
+            let sample_var = sum_sq / n as f64 - sample_mean * sample_mean;
+            assert!((sample_mean - mean).abs() < 0.01 * mean);
+            assert!((sample_var - var).abs() < 0.03 * var);
+        }
+    }
+
+    /// Tests that `next_negative_binomial` returns promptly with a huge `r`.
+    #[cfg(feature = "std")]
+    #[test]
+    fn next_negative_binomial_huge_r() {
+        let mut g = Pcg32::new(0x3c6e_f372_fe94_f82b, 0xa54f_f53a_5f1d_36f1);
+        let (r, p) = (1_000_000_000, 0.5);
+        let n = 100_000;
+        let sum: f64 = (0..n).map(|_| g.next_negative_binomial(r, p) as f64).sum();
+        let mean = r as f64 * (1.0 - p) / p;
+        let std_err = (mean / p / n as f64).sqrt();
+        assert!((sum / n as f64 - mean).abs() < 5.0 * std_err);
+    }
+
+    /// Tests that the perturbations of `add_noise` have mean zero and standard deviation `sigma`.
+    #[cfg(feature = "std")]
+    #[test]
+    fn add_noise() {
+        use alloc::vec::Vec;
+
+        let mut g = Pcg32::new(0x2ffd_72db_d01a_dfb7, 0xb8e1_afed_6a26_7e96);
+        let original: Vec<f64> = (0..100_000).map(|i| i as f64 * 0.5).collect();
+        for sigma in [0.0, 0.1, 2.0] {
+            let mut data = original.clone();
+            g.add_noise(&mut data, sigma);
+            let n = data.len() as f64;
+            let noise = data.iter().zip(&original).map(|(x, y)| x - y);
+            let mean = noise.clone().sum::<f64>() / n;
+            let sd = (noise.map(|d| (d - mean).powi(2)).sum::<f64>() / n).sqrt();
+            assert!(mean.abs() < 0.01 * sigma.max(1.0));
+            assert!((sd - sigma).abs() < 0.01 * sigma.max(1.0));
+        }
+    }
+
+    /// Tests that the overall true rate of `gen_bool_beta` approaches `alpha / (alpha + beta)`.
+    #[cfg(feature = "std")]
+    #[test]
+    fn gen_bool_beta() {
+        let mut g = Pcg32::new(0x71d3_94b8_2a0c_e65f, 0x3e9b_57a1_c40d_82f6);
+        let n = 100_000;
+        for (alpha, beta) in [(2.0, 5.0), (0.5, 0.5), (0.1, 3.0), (30.0, 10.0)] {
+            let hits = (0..n).filter(|_| g.gen_bool_beta(alpha, beta)).count();
+            assert!((hits as f64 / n as f64 - alpha / (alpha + beta)).abs() < 0.01);
+        }
+    }
+
+    /// Tests that `gen_bool_beta` rejects a non-positive shape.
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic]
+    fn gen_bool_beta_zero_alpha() {
+        Pcg32::default().gen_bool_beta(0.0, 1.0);
+    }
+}
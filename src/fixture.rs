@@ -0,0 +1,457 @@
+//! Helpers to fabricate test fixtures and other structured data.
+
+use crate::Pcg32;
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
+
+/// Largest palette size accepted by [`Pcg32::gen_palette`], far below the number of distinct
+/// colors within its saturation and value ranges so that redrawing duplicates stays cheap.
+#[cfg(feature = "alloc")]
+const PALETTE_MAX: usize = 10_000;
+
+/// Special-purpose IPv4 blocks as `(network, prefix length)` pairs, which are excluded by
+/// [`Pcg32::gen_ipv4_public`].
+const RESERVED_IPV4: [(u32, u32); 15] = [
+    (0x0000_0000, 8),  // 0.0.0.0/8 "this network"
+    (0x0a00_0000, 8),  // 10.0.0.0/8 private
+    (0x6440_0000, 10), // 100.64.0.0/10 shared address space
+    (0x7f00_0000, 8),  // 127.0.0.0/8 loopback
+    (0xa9fe_0000, 16), // 169.254.0.0/16 link local
+    (0xac10_0000, 12), // 172.16.0.0/12 private
+    (0xc000_0000, 24), // 192.0.0.0/24 IETF protocol assignments
+    (0xc000_0200, 24), // 192.0.2.0/24 documentation
+    (0xc058_6300, 24), // 192.88.99.0/24 6to4 relay anycast
+    (0xc0a8_0000, 16), // 192.168.0.0/16 private
+    (0xc612_0000, 15), // 198.18.0.0/15 benchmarking
+    (0xc633_6400, 24), // 198.51.100.0/24 documentation
+    (0xcb00_7100, 24), // 203.0.113.0/24 documentation
+    (0xe000_0000, 4),  // 224.0.0.0/4 multicast
+    (0xf000_0000, 4),  // 240.0.0.0/4 reserved and broadcast
+];
+
+impl Pcg32 {
+    /// Generates `count` records, each consisting of `fields` pseudorandom 32-bit unsigned
+    /// integers.
+    ///
+    /// The values are filled in record by record, so flattening the result yields the same
+    /// sequence as `fields * count` successive calls to [`generate`](Self::generate).
+    #[cfg(feature = "alloc")]
+    pub fn gen_records(&mut self, fields: usize, count: usize) -> Vec<Vec<u32>> {
+        (0..count)
+            .map(|_| (0..fields).map(|_| self.generate()).collect())
+            .collect()
+    }
+
+    /// Generates the skeleton of a random nested structure as the number of nodes at each of
+    /// `depth` levels, each drawn uniformly from `0..=branching`.
+    ///
+    /// This is useful to shape fabricated nested test data such as JSON documents, e.g., by
+    /// giving each level the returned number of children.
+    #[cfg(feature = "alloc")]
+    pub fn gen_random_tree_sizes(&mut self, depth: usize, branching: usize) -> Vec<usize> {
+        (0..depth)
+            .map(|_| match branching.checked_add(1) {
+                Some(n) => self.gen_index(n),
+                None => self.next_u64() as usize,
+            })
+            .collect()
+    }
+
+    /// Generates an `R` × `C` matrix of pseudorandom 32-bit unsigned integers.
+    ///
+    /// The matrix is filled in row-major order, so flattening the result yields the same
+    /// sequence as `R * C` successive calls to [`generate`](Self::generate).
+    pub fn fill_matrix<const R: usize, const C: usize>(&mut self) -> [[u32; C]; R] {
+        let mut matrix = [[0; C]; R];
+        for e in matrix.iter_mut().flatten() {
+            *e = self.generate();
+        }
+        matrix
+    }
+
+    /// Fills a row-major `width` × `height` grid of booleans, setting each cell to `true`
+    /// independently with probability `density`.
+    ///
+    /// This is useful to seed cellular automata such as Conway's Game of Life.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out.len()` is not `width * height` or if `density` is not in `[0, 1]`.
+    pub fn fill_bool_grid(&mut self, width: usize, height: usize, out: &mut [bool], density: f64) {
+        assert_eq!(
+            Some(out.len()),
+            width.checked_mul(height),
+            "grid buffer length must be width * height"
+        );
+        assert!((0.0..=1.0).contains(&density), "density must be in [0, 1]");
+        for cell in out {
+            *cell = self.next_f64() < density;
+        }
+    }
+
+    /// Generates a uniformly distributed Unicode scalar value.
+    ///
+    /// Every code point in `0..=0x10FFFF` except the surrogates `0xD800..=0xDFFF` is returned with
+    /// equal probability, which is useful to fuzz text handling code.
+    pub fn gen_char(&mut self) -> char {
+        const SURROGATES: u32 = 0xE000 - 0xD800;
+        let x = self.bounded_u32(0x11_0000 - SURROGATES);
+        let x = if x < 0xD800 { x } else { x + SURROGATES };
+        match char::from_u32(x) {
+            Some(c) => c,
+            None => unreachable!(),
+        }
+    }
+
+    /// Generates a uniformly distributed IPv4 address as four octets in network order.
+    pub fn gen_ipv4(&mut self) -> [u8; 4] {
+        self.generate().to_be_bytes()
+    }
+
+    /// Generates an IPv4 address uniformly distributed over the publicly routable unicast space.
+    ///
+    /// Addresses are drawn by [`gen_ipv4`](Self::gen_ipv4) and rejected if they fall in a
+    /// private, loopback, link-local, documentation, multicast, or otherwise reserved block.
+    pub fn gen_ipv4_public(&mut self) -> [u8; 4] {
+        loop {
+            let addr = self.generate();
+            if !RESERVED_IPV4
+                .iter()
+                .any(|&(net, len)| (addr ^ net) >> (32 - len) == 0)
+            {
+                return addr.to_be_bytes();
+            }
+        }
+    }
+
+    /// Generates a random unicast MAC address.
+    ///
+    /// The first octet has the locally administered bit set and the multicast bit cleared, so
+    /// the address never collides with a vendor-assigned one.
+    pub fn gen_mac(&mut self) -> [u8; 6] {
+        let mut mac = [0; 6];
+        self.fill_bytes(&mut mac);
+        mac[0] = mac[0] & 0xfc | 0x02;
+        mac
+    }
+
+    /// Generates a palette of `n` distinct RGB colors spread around the hue wheel.
+    ///
+    /// The hue starts at a random angle and advances by the golden ratio conjugate of a turn
+    /// for each color, which keeps successive colors well separated however many are requested.
+    /// Saturation and value are jittered in `[0.5, 0.8)` and `[0.75, 0.95)`, respectively, to
+    /// avoid an overly uniform look, and redrawn whenever the quantized color duplicates an
+    /// earlier one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` exceeds 10,000.
+    #[cfg(feature = "alloc")]
+    pub fn gen_palette(&mut self, n: usize) -> Vec<[u8; 3]> {
+        const GOLDEN_RATIO_CONJUGATE: f64 = 0.618_033_988_749_894_9;
+
+        assert!(n <= PALETTE_MAX, "n must not exceed 10,000");
+        let mut seen = BTreeSet::new();
+        let mut hue = self.next_f64();
+        (0..n)
+            .map(|_| {
+                let rgb = loop {
+                    let s = 0.5 + 0.3 * self.next_f64();
+                    let v = 0.75 + 0.2 * self.next_f64();
+                    let rgb = hsv_to_rgb(hue, s, v);
+                    if seen.insert(rgb) {
+                        break rgb;
+                    }
+                };
+                hue = (hue + GOLDEN_RATIO_CONJUGATE) % 1.0;
+                rgb
+            })
+            .collect()
+    }
+
+    /// Generates a string of `len` characters, each chosen uniformly at random from `alphabet`.
+    ///
+    /// The characters are picked by unbiased bounded integer generation, so every character of
+    /// the alphabet is equally likely regardless of its size. Characters listed multiple times
+    /// are proportionally more likely.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alphabet` is empty.
+    #[cfg(feature = "alloc")]
+    pub fn gen_string_from(&mut self, alphabet: &[char], len: usize) -> String {
+        assert!(!alphabet.is_empty(), "alphabet must not be empty");
+        (0..len)
+            .map(|_| alphabet[self.gen_index(alphabet.len())])
+            .collect()
+    }
+
+    /// Generates a random ASCII password of `len` characters.
+    ///
+    /// If `len >= 4`, the password contains at least one lowercase letter, uppercase letter,
+    /// digit, and symbol: one character is drawn from each class, the rest are drawn from all
+    /// classes combined, and the characters are shuffled. Shorter passwords are drawn from all
+    /// classes without guarantees.
+    ///
+    /// This generator is not cryptographically secure, so the result is only suitable for test
+    /// fixtures and must not be used as an actual credential.
+    #[cfg(feature = "alloc")]
+    pub fn gen_password(&mut self, len: usize) -> String {
+        const LOWER: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+        const UPPER: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        const DIGIT: &[u8] = b"0123456789";
+        const SYMBOL: &[u8] = b"!#$%&()*+,-./:;<=>?@[]^_{|}~";
+        const ALL: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ\
+            0123456789!#$%&()*+,-./:;<=>?@[]^_{|}~";
+
+        let mut chars = Vec::with_capacity(len);
+        if len >= 4 {
+            for class in [LOWER, UPPER, DIGIT, SYMBOL] {
+                chars.push(class[self.gen_index(class.len())]);
+            }
+        }
+        while chars.len() < len {
+            chars.push(ALL[self.gen_index(ALL.len())]);
+        }
+        self.shuffle(&mut chars);
+        chars.into_iter().map(char::from).collect()
+    }
+}
+
+/// Converts a color from HSV with components in `[0, 1)` to 8-bit RGB.
+#[cfg(feature = "alloc")]
+fn hsv_to_rgb(h: f64, s: f64, v: f64) -> [u8; 3] {
+    let h6 = h * 6.0;
+    let f = h6 - (h6 as u32) as f64;
+    let (p, q, t) = (v * (1.0 - s), v * (1.0 - s * f), v * (1.0 - s * (1.0 - f)));
+    let (r, g, b) = match h6 as u32 {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    };
+    [r, g, b].map(|c| (c * 255.0 + 0.5) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    /// Tests that `gen_records` returns the requested dimensions in generation order.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn gen_records() {
+        let (fields, count) = (7, 13);
+        let mut g = Pcg32::new(0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210);
+        let mut h = g.clone();
+
+        let records = g.gen_records(fields, count);
+        assert_eq!(records.len(), count);
+        for record in &records {
+            assert_eq!(record.len(), fields);
+        }
+        for e in records.into_iter().flatten() {
+            assert_eq!(e, h.generate());
+        }
+        assert_eq!(g, h);
+
+        assert!(g.gen_records(0, 3).iter().all(|r| r.is_empty()));
+        assert!(g.gen_records(3, 0).is_empty());
+        assert_eq!(g, h);
+    }
+
+    /// Tests that `gen_random_tree_sizes` returns deterministic sizes within the bound.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn gen_random_tree_sizes() {
+        let g = Pcg32::new(0x1405_7b7e_f767_814f, 0x5851_f42d_4c95_7f2d);
+        for (depth, branching) in [(0, 3), (5, 0), (10, 1), (1000, 4)] {
+            let sizes = g.clone().gen_random_tree_sizes(depth, branching);
+            assert_eq!(sizes, g.clone().gen_random_tree_sizes(depth, branching));
+            assert_eq!(sizes.len(), depth);
+            assert!(sizes.iter().all(|&s| s <= branching));
+            if depth >= 1000 {
+                assert!((0..=branching).all(|b| sizes.contains(&b)));
+            }
+        }
+    }
+
+    /// Tests that `fill_matrix` fills the matrix in row-major generation order.
+    #[test]
+    fn fill_matrix() {
+        let mut g = Pcg32::new(0x5851_f42d_4c95_7f2d, 0x1405_7b7e_f767_814f);
+        let mut h = g.clone();
+
+        let matrix: [[u32; 5]; 3] = g.fill_matrix();
+        for row in matrix {
+            for e in row {
+                assert_eq!(e, h.generate());
+            }
+        }
+        assert_eq!(g, h);
+
+        let _: [[u32; 0]; 4] = g.fill_matrix();
+        let _: [[u32; 4]; 0] = g.fill_matrix();
+        assert_eq!(g, h);
+    }
+
+    /// Tests that the fraction of live cells from `fill_bool_grid` approximates the density.
+    #[test]
+    fn fill_bool_grid() {
+        let mut g = Pcg32::new(0x3c6e_f372_fe94_f82b, 0xa54f_f53a_5f1d_36f1);
+        let (width, height) = (128, 96);
+        let mut grid = [false; 128 * 96];
+        for density in [0.0, 0.1, 0.5, 0.9, 1.0] {
+            g.fill_bool_grid(width, height, &mut grid, density);
+            let live = grid.iter().filter(|&&c| c).count();
+            assert!((live as f64 / grid.len() as f64 - density).abs() < 0.02);
+        }
+    }
+
+    /// Tests that `fill_bool_grid` rejects a buffer of the wrong length.
+    #[test]
+    #[should_panic]
+    fn fill_bool_grid_length_mismatch() {
+        let mut grid = [false; 10];
+        Pcg32::default().fill_bool_grid(3, 4, &mut grid, 0.5);
+    }
+
+    /// Tests that `gen_char` never returns surrogates and covers the whole code space.
+    #[test]
+    fn gen_char() {
+        let mut g = Pcg32::new(0xa409_3822_299f_31d0, 0x082e_fa98_ec4e_6c89);
+        let mut planes = [0u32; 17];
+        let mut around_surrogates = [false; 2];
+        for _ in 0..200_000 {
+            let c = g.gen_char() as u32;
+            assert!(!(0xD800..=0xDFFF).contains(&c));
+            planes[c as usize >> 16] += 1;
+            around_surrogates[0] |= (0xD000..0xD800).contains(&c);
+            around_surrogates[1] |= (0xE000..0xE800).contains(&c);
+        }
+        assert!(planes.iter().all(|&n| n > 0));
+        assert_eq!(around_surrogates, [true; 2]);
+    }
+
+    /// Tests that `gen_ipv4_public` never returns addresses in reserved blocks.
+    #[test]
+    fn gen_ipv4_public() {
+        let mut g = Pcg32::new(0x9b05_688c_2b3e_6c1f, 0x1f83_d9ab_fb41_bd6b);
+        let (mut private, mut public) = (0, 0);
+        for _ in 0..100_000 {
+            let [a, b, ..] = g.gen_ipv4();
+            private += (a == 10 || a == 127 || (a, b) == (192, 168)) as u32;
+
+            let [a, b, c, _] = g.gen_ipv4_public();
+            assert!(!matches!(a, 0 | 10 | 127 | 224..));
+            assert!(!matches!((a, b), (192, 168) | (169, 254) | (172, 16..=31)));
+            assert!(!matches!(
+                (a, b, c),
+                (192, 0, 2) | (198, 51, 100) | (203, 0, 113)
+            ));
+            public += (a == 8) as u32;
+        }
+        assert!(private > 0);
+        assert!(public > 0);
+    }
+
+    /// Tests that `gen_mac` returns varying locally administered unicast addresses.
+    #[test]
+    fn gen_mac() {
+        let mut g = Pcg32::new(0x5be0_cd19_137e_2179, 0x3c6e_f372_a54f_f53a);
+        let first = g.gen_mac();
+        let mut varies = [false; 6];
+        for _ in 0..1000 {
+            let mac = g.gen_mac();
+            assert_eq!(mac[0] & 0x03, 0x02);
+            for (v, (a, b)) in varies.iter_mut().zip(mac.iter().zip(&first)) {
+                *v |= a != b;
+            }
+        }
+        assert_eq!(varies, [true; 6]);
+    }
+
+    /// Tests that `gen_palette` returns deterministic and distinct colors.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn gen_palette() {
+        let g = Pcg32::new(0x6a09_e667_f3bc_c908, 0x510e_527f_ade6_82d1);
+        for n in [0, 1, 8, 64, 1000, 10_000] {
+            let palette = g.clone().gen_palette(n);
+            assert_eq!(palette, g.clone().gen_palette(n));
+            assert_eq!(palette.len(), n);
+            let mut sorted = palette.clone();
+            sorted.sort_unstable();
+            sorted.dedup();
+            assert_eq!(sorted.len(), n);
+            for a in &palette {
+                // value is at least 0.75 and saturation at least 0.5
+                let (max, min) = (a.iter().max().unwrap(), a.iter().min().unwrap());
+                assert!(*max >= 191 && *max <= 243);
+                assert!(*min as f64 <= *max as f64 * 0.5 + 1.0);
+            }
+        }
+        assert_ne!(g.clone().gen_palette(8), Pcg32::default().gen_palette(8));
+    }
+
+    /// Tests that `gen_palette` rejects more colors than it can keep distinct cheaply.
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic]
+    fn gen_palette_too_many() {
+        Pcg32::default().gen_palette(10_001);
+    }
+
+    /// Tests that `gen_string_from` draws exactly `len` characters from the alphabet.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn gen_string_from() {
+        let mut g = Pcg32::new(0x8cc7_0208_1a64_39ec, 0x2de9_2c6f_592b_0275);
+        let alphabet = ['a', 'ß', '漢', '🦀', '0'];
+        let mut freq = [0u32; 5];
+        for len in [0, 1, 7, 1000] {
+            let s = g.gen_string_from(&alphabet, len);
+            assert_eq!(s.chars().count(), len);
+            for c in s.chars() {
+                freq[alphabet.iter().position(|&a| a == c).unwrap()] += 1;
+            }
+        }
+        assert!(freq.iter().all(|&f| (f as i32 - 1008 / 5).abs() < 40));
+    }
+
+    /// Tests that `gen_string_from` rejects an empty alphabet.
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic]
+    fn gen_string_from_empty_alphabet() {
+        Pcg32::default().gen_string_from(&[], 3);
+    }
+
+    /// Tests that `gen_password` returns passwords containing every character class.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn gen_password() {
+        let mut g = Pcg32::new(0x9b05_688c_2b3e_6c1f, 0x5be0_cd19_137e_2179);
+        for len in [0, 1, 3, 4, 5, 8, 16, 64] {
+            for _ in 0..100 {
+                let password = g.gen_password(len);
+                assert_eq!(password.len(), len);
+                assert!(password.bytes().all(|c| c.is_ascii_graphic()));
+                assert!(!password.contains(['"', '\'', '\\', '`']));
+                if len >= 4 {
+                    assert!(password.bytes().any(|c| c.is_ascii_lowercase()));
+                    assert!(password.bytes().any(|c| c.is_ascii_uppercase()));
+                    assert!(password.bytes().any(|c| c.is_ascii_digit()));
+                    assert!(password.bytes().any(|c| c.is_ascii_punctuation()));
+                }
+            }
+        }
+
+        // the guaranteed characters are not always at the front
+        let starts = (0..100).map(|_| g.gen_password(4).as_bytes()[0]);
+        assert!(starts.filter(|c| c.is_ascii_digit()).count() > 10);
+    }
+}
@@ -0,0 +1,241 @@
+//! Random points in geometric shapes.
+
+use crate::Pcg32;
+
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+impl Pcg32 {
+    /// Generates a uniformly distributed point inside the axis-aligned box spanned by `min` and
+    /// `max`.
+    ///
+    /// Each coordinate is drawn from the half-open interval `[min[i], max[i])`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any bound is not finite or `min[i] >= max[i]` for some `i`.
+    #[cfg(feature = "std")]
+    pub fn gen_point_in_box(&mut self, min: [f64; 3], max: [f64; 3]) -> [f64; 3] {
+        for i in 0..3 {
+            assert!(
+                min[i].is_finite() && max[i].is_finite() && min[i] < max[i],
+                "box bounds must be finite and satisfy min < max"
+            );
+        }
+        core::array::from_fn(|i| self.uniform_f64(min[i], max[i]))
+    }
+
+    /// Generates a complex number `(re, im)` uniformly distributed inside the unit disk.
+    ///
+    /// Points are drawn from the enclosing square and rejected until one falls inside the disk,
+    /// which takes `4 / π ≈ 1.27` attempts on average.
+    #[cfg(feature = "std")]
+    pub fn gen_complex_unit_disk(&mut self) -> (f64, f64) {
+        loop {
+            let re = self.uniform_f64(-1.0, 1.0);
+            let im = self.uniform_f64(-1.0, 1.0);
+            if re * re + im * im <= 1.0 {
+                return (re, im);
+            }
+        }
+    }
+
+    /// Generates a uniformly distributed unit quaternion `[w, x, y, z]`, which represents a
+    /// uniformly random 3D rotation.
+    ///
+    /// This uses Shoemake's method, mapping three uniforms to a point on the unit 3-sphere.
+    #[cfg(feature = "std")]
+    pub fn gen_quaternion(&mut self) -> [f64; 4] {
+        use core::f64::consts::TAU;
+
+        let u1 = self.next_f64();
+        let (s1, c1) = (TAU * self.next_f64()).sin_cos();
+        let (s2, c2) = (TAU * self.next_f64()).sin_cos();
+        let (r1, r2) = ((1.0 - u1).sqrt(), u1.sqrt());
+        [r1 * s1, r1 * c1, r2 * s2, r2 * c2]
+    }
+
+    /// Generates a uniformly distributed point inside the triangle with vertices `a`, `b`, and
+    /// `c`.
+    ///
+    /// This uses the barycentric method with the square root of the first uniform, which maps
+    /// the unit square onto the triangle without rejection or distortion.
+    #[cfg(feature = "std")]
+    pub fn gen_point_in_triangle(&mut self, a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> [f64; 2] {
+        let s = self.next_f64().sqrt();
+        let r = self.next_f64();
+        let (wa, wb, wc) = (1.0 - s, s * (1.0 - r), s * r);
+        core::array::from_fn(|i| wa * a[i] + wb * b[i] + wc * c[i])
+    }
+
+    /// Generates `clusters * per_cluster` points clustered around random centers, following a
+    /// Thomas point process.
+    ///
+    /// The cluster centers are placed uniformly in the unit square, and each point is displaced
+    /// from its center by independent normal offsets with standard deviation `spread` along
+    /// each axis, so points may fall outside the square. The points are grouped by cluster,
+    /// i.e., the `i`-th chunk of `per_cluster` points belongs to the `i`-th cluster.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `spread` is negative or not finite.
+    #[cfg(feature = "std")]
+    pub fn gen_clustered_points(
+        &mut self,
+        clusters: usize,
+        per_cluster: usize,
+        spread: f64,
+    ) -> Vec<(f64, f64)> {
+        assert!(
+            spread.is_finite() && spread >= 0.0,
+            "spread must be finite and non-negative"
+        );
+        let mut points = Vec::with_capacity(clusters.saturating_mul(per_cluster));
+        for _ in 0..clusters {
+            let (cx, cy) = (self.next_f64(), self.next_f64());
+            for _ in 0..per_cluster {
+                points.push((self.next_normal(cx, spread), self.next_normal(cy, spread)));
+            }
+        }
+        points
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::Pcg32;
+
+    /// Tests that `gen_point_in_box` returns points within the box.
+    #[test]
+    fn gen_point_in_box() {
+        let mut g = Pcg32::new(0x4528_21e6_38d0_1377, 0xbe54_66cf_34e9_0c6c);
+        let (min, max) = ([-1.0, 0.0, 1e6], [1.0, 1e-9, 1e6 + 1.0]);
+        let mut sum = [0.0; 3];
+        let n = 10_000;
+        for _ in 0..n {
+            let p = g.gen_point_in_box(min, max);
+            for i in 0..3 {
+                assert!(min[i] <= p[i] && p[i] < max[i]);
+                sum[i] += (p[i] - min[i]) / (max[i] - min[i]);
+            }
+        }
+        for s in sum {
+            assert!((s / n as f64 - 0.5).abs() < 0.02);
+        }
+    }
+
+    /// Tests that `gen_complex_unit_disk` returns points uniformly distributed in the disk.
+    #[test]
+    fn gen_complex_unit_disk() {
+        let mut g = Pcg32::new(0x1f83_d9ab_5be0_cd19, 0x6a09_e667_bb67_ae85);
+        let n = 100_000;
+        let (mut sum_r2, mut quadrants) = (0.0, [0u32; 4]);
+        for _ in 0..n {
+            let (re, im) = g.gen_complex_unit_disk();
+            let r2 = re * re + im * im;
+            assert!(r2 <= 1.0);
+            sum_r2 += r2;
+            quadrants[(re < 0.0) as usize * 2 + (im < 0.0) as usize] += 1;
+        }
+        // the squared radius of a uniform point in the disk is uniform in [0, 1]
+        assert!((sum_r2 / n as f64 - 0.5).abs() < 0.01);
+        for q in quadrants {
+            assert!((q as f64 / n as f64 - 0.25).abs() < 0.01);
+        }
+    }
+
+    /// Tests that `gen_quaternion` returns unit quaternions covering all orientations.
+    #[test]
+    fn gen_quaternion() {
+        let mut g = Pcg32::new(0x5cb0_a9dc_76f9_88da, 0x983e_5152_a831_c66d);
+        let n = 80_000;
+        let mut octants = [0u32; 8];
+        let mut sum_sq = [0.0; 4];
+        for _ in 0..n {
+            let q = g.gen_quaternion();
+            assert!((q.iter().map(|c| c * c).sum::<f64>() - 1.0).abs() < 1e-12);
+            for (s, c) in sum_sq.iter_mut().zip(q) {
+                *s += c * c;
+            }
+
+            // rotate the z axis by the quaternion
+            let [w, x, y, z] = q;
+            let v = [
+                2.0 * (x * z + w * y),
+                2.0 * (y * z - w * x),
+                1.0 - 2.0 * (x * x + y * y),
+            ];
+            octants[v.iter().fold(0, |acc, &c| acc * 2 + (c < 0.0) as usize)] += 1;
+        }
+        for s in sum_sq {
+            assert!((s / n as f64 - 0.25).abs() < 0.01);
+        }
+        for o in octants {
+            assert!((o as f64 / n as f64 - 0.125).abs() < 0.01);
+        }
+    }
+
+    /// Tests that `gen_point_in_triangle` returns points uniformly distributed in the triangle.
+    #[test]
+    fn gen_point_in_triangle() {
+        let mut g = Pcg32::new(0x2f2a_8c6b_1d4e_7a93, 0x6e2d_3b55_0c4f_91a8);
+        let (a, b, c) = ([-1.0, 0.5], [3.0, -2.0], [0.5, 4.0]);
+        let area2 = (b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1]);
+        let n = 50_000;
+        let mut sum = [0.0; 3];
+        for _ in 0..n {
+            let p = g.gen_point_in_triangle(a, b, c);
+            let cross = |u: [f64; 2], v: [f64; 2]| {
+                ((u[0] - p[0]) * (v[1] - p[1]) - (v[0] - p[0]) * (u[1] - p[1])) / area2
+            };
+            let bary = [cross(b, c), cross(c, a), cross(a, b)];
+            assert!(bary.iter().all(|&l| l >= -1e-12));
+            assert!((bary.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+            for (s, l) in sum.iter_mut().zip(bary) {
+                *s += l;
+            }
+        }
+        // the centroid of a uniform triangle has equal barycentric coordinates
+        for s in sum {
+            assert!((s / n as f64 - 1.0 / 3.0).abs() < 0.01);
+        }
+    }
+
+    /// Tests that `gen_clustered_points` scatters the requested points around each center.
+    #[test]
+    fn gen_clustered_points() {
+        let mut g = Pcg32::new(0x3b4c_1d2e_8f90_a7b6, 0x51c3_e2d4_f6a8_9b07);
+        let (clusters, per_cluster, spread) = (20, 500, 0.01);
+        let points = g.gen_clustered_points(clusters, per_cluster, spread);
+        assert_eq!(points.len(), clusters * per_cluster);
+        for chunk in points.chunks(per_cluster) {
+            let n = per_cluster as f64;
+            let (mx, my) = chunk
+                .iter()
+                .fold((0.0, 0.0), |(x, y), p| (x + p.0, y + p.1));
+            let (mx, my) = (mx / n, my / n);
+            assert!((-0.01..1.01).contains(&mx) && (-0.01..1.01).contains(&my));
+            let var = chunk
+                .iter()
+                .map(|p| (p.0 - mx).powi(2) + (p.1 - my).powi(2))
+                .sum::<f64>()
+                / (2.0 * n);
+            assert!((var.sqrt() / spread - 1.0).abs() < 0.1);
+            assert!(chunk
+                .iter()
+                .all(|p| (p.0 - mx).hypot(p.1 - my) < 6.0 * spread));
+        }
+
+        assert!(g.gen_clustered_points(0, 10, 0.1).is_empty());
+        assert!(g.gen_clustered_points(10, 0, 0.1).is_empty());
+        let tight = g.gen_clustered_points(3, 4, 0.0);
+        assert!(tight.chunks(4).all(|c| c.iter().all(|&p| p == c[0])));
+    }
+
+    /// Tests that `gen_point_in_box` rejects an empty box.
+    #[test]
+    #[should_panic]
+    fn gen_point_in_box_empty() {
+        Pcg32::default().gen_point_in_box([0.0, 0.0, 0.0], [1.0, 0.0, 1.0]);
+    }
+}
@@ -0,0 +1,422 @@
+//! Random graphs and related structures for testing graph algorithms.
+
+use crate::Pcg32;
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+impl Pcg32 {
+    /// Generates a random directed acyclic graph on `n` nodes as a list of edges `(u, v)`.
+    ///
+    /// Each pair `u < v` is connected independently with probability `edge_prob`. Since every
+    /// edge points from a smaller to a larger node, the graph is acyclic by construction and
+    /// `0..n` is a topological order. The edges are listed in lexicographic order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `edge_prob` is not in `[0, 1]`.
+    #[cfg(feature = "alloc")]
+    pub fn gen_random_dag(&mut self, n: usize, edge_prob: f64) -> Vec<(usize, usize)> {
+        assert!(
+            (0.0..=1.0).contains(&edge_prob),
+            "edge_prob must be in [0, 1]"
+        );
+        let mut edges = Vec::new();
+        for u in 0..n {
+            for v in u + 1..n {
+                if self.next_f64() < edge_prob {
+                    edges.push((u, v));
+                }
+            }
+        }
+        edges
+    }
+
+    /// Generates a random undirected weighted graph on `n` nodes as a list of edges
+    /// `(u, v, w)` with `u < v`.
+    ///
+    /// Each pair of nodes is connected independently with probability `edge_prob`, and each
+    /// edge is given a weight `w` uniformly distributed in `1..=max_weight`. The edges are
+    /// listed in lexicographic order of `(u, v)`, which makes the output suitable for
+    /// deterministic benchmark fixtures.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `edge_prob` is not in `[0, 1]` or `max_weight` is zero.
+    #[cfg(feature = "alloc")]
+    pub fn gen_weighted_graph(
+        &mut self,
+        n: usize,
+        edge_prob: f64,
+        max_weight: u32,
+    ) -> Vec<(usize, usize, u32)> {
+        assert!(
+            (0.0..=1.0).contains(&edge_prob),
+            "edge_prob must be in [0, 1]"
+        );
+        assert!(max_weight > 0, "max_weight must be nonzero");
+        let mut edges = Vec::new();
+        for u in 0..n {
+            for v in u + 1..n {
+                if self.next_f64() < edge_prob {
+                    edges.push((u, v, 1 + self.bounded_u32(max_weight)));
+                }
+            }
+        }
+        edges
+    }
+
+    /// Generates a perfect maze of `width` × `height` cells by randomized depth-first search.
+    ///
+    /// The maze is returned as a row-major grid of `(2 * width + 1) * (2 * height + 1)` tiles,
+    /// where `true` denotes a wall. Cell `(x, y)` is located at tile `(2 * x + 1, 2 * y + 1)`,
+    /// and the tiles between adjacent cells are opened to form passages. Every cell is reachable
+    /// from every other through exactly one path.
+    #[cfg(feature = "alloc")]
+    pub fn gen_maze(&mut self, width: usize, height: usize) -> Vec<bool> {
+        let cols = 2 * width + 1;
+        let tile = |x: usize, y: usize| (2 * y + 1) * cols + 2 * x + 1;
+        let mut walls = vec![true; cols * (2 * height + 1)];
+        if width == 0 || height == 0 {
+            return walls;
+        }
+
+        walls[tile(0, 0)] = false;
+        let mut stack = vec![(0, 0)];
+        while let Some(&(x, y)) = stack.last() {
+            let mut candidates = [(0, 0); 4];
+            let mut n = 0;
+            let neighbors = [
+                (x > 0).then(|| (x - 1, y)),
+                (x + 1 < width).then_some((x + 1, y)),
+                (y > 0).then(|| (x, y - 1)),
+                (y + 1 < height).then_some((x, y + 1)),
+            ];
+            for (nx, ny) in neighbors.into_iter().flatten() {
+                if walls[tile(nx, ny)] {
+                    candidates[n] = (nx, ny);
+                    n += 1;
+                }
+            }
+            if n == 0 {
+                stack.pop();
+                continue;
+            }
+            let (nx, ny) = candidates[self.gen_index(n)];
+            walls[(tile(x, y) + tile(nx, ny)) / 2] = false;
+            walls[tile(nx, ny)] = false;
+            stack.push((nx, ny));
+        }
+        walls
+    }
+
+    /// Generates a random tree on `n` nodes rooted at node `0` as a parent array.
+    ///
+    /// Each node `i > 0` picks its parent uniformly from `0..i`, which guarantees a single
+    /// connected tree. The root is its own parent, i.e., `parents[0] == 0`.
+    #[cfg(feature = "alloc")]
+    pub fn gen_random_tree(&mut self, n: usize) -> Vec<usize> {
+        (0..n)
+            .map(|i| if i == 0 { 0 } else { self.gen_index(i) })
+            .collect()
+    }
+
+    /// Returns a random permutation of the edge indices `0..edges`.
+    ///
+    /// Processing the edges of a graph in this order in Kruskal's algorithm yields a random
+    /// spanning tree, which is a common way to generate mazes and random connected graphs. The
+    /// result is the same as collecting [`shuffled_range`](Self::shuffled_range).
+    #[cfg(feature = "alloc")]
+    pub fn random_edge_order(&mut self, edges: usize) -> Vec<usize> {
+        self.shuffled_range(edges).collect()
+    }
+
+    /// Performs a random walk of up to `steps` steps on the graph given by `adjacency` lists,
+    /// returning the visited nodes including `start`.
+    ///
+    /// Each step moves to a neighbor of the current node chosen uniformly at random, where
+    /// repeated entries in an adjacency list count as parallel edges. The walk stops early at a
+    /// node without neighbors, so the result has at most `steps + 1` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` or a visited neighbor is not a valid index into `adjacency`.
+    #[cfg(feature = "alloc")]
+    pub fn random_walk(
+        &mut self,
+        adjacency: &[Vec<usize>],
+        start: usize,
+        steps: usize,
+    ) -> Vec<usize> {
+        assert!(start < adjacency.len(), "start must be a node of the graph");
+        let mut walk = vec![start];
+        let mut node = start;
+        for _ in 0..steps {
+            match self.choose_copied(&adjacency[node]) {
+                Some(next) => node = next,
+                None => break,
+            }
+            walk.push(node);
+        }
+        walk
+    }
+
+    /// Generates a row-major `width` × `height` grid of obstacles, where `true` denotes a
+    /// blocked cell, such that the top-left and bottom-right corners are connected.
+    ///
+    /// Each cell is blocked independently with probability `density`, except the two corners,
+    /// which are always open. If the corners end up disconnected, a random monotone path
+    /// between them is carved open, so the obstacle fraction may fall slightly below `density`
+    /// in dense maps. Cells are connected through their four orthogonal neighbors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is zero or `density` is not in `[0, 1]`.
+    #[cfg(feature = "alloc")]
+    pub fn gen_obstacle_map(&mut self, width: usize, height: usize, density: f64) -> Vec<bool> {
+        assert!(width > 0 && height > 0, "map must not be empty");
+        assert!((0.0..=1.0).contains(&density), "density must be in [0, 1]");
+        let mut map: Vec<bool> = (0..width * height)
+            .map(|_| self.next_f64() < density)
+            .collect();
+        map[0] = false;
+        map[width * height - 1] = false;
+
+        if !corners_connected(&map, width, height) {
+            let (mut x, mut y) = (0, 0);
+            for right in self.gen_schedule(width + height - 2, width - 1) {
+                if right {
+                    x += 1;
+                } else {
+                    y += 1;
+                }
+                map[y * width + x] = false;
+            }
+        }
+        map
+    }
+}
+
+/// Determines whether the top-left and bottom-right cells of a row-major obstacle map are
+/// connected through open cells.
+#[cfg(feature = "alloc")]
+fn corners_connected(map: &[bool], width: usize, height: usize) -> bool {
+    let mut visited = vec![false; map.len()];
+    let mut stack = vec![0];
+    visited[0] = true;
+    while let Some(i) = stack.pop() {
+        if i == map.len() - 1 {
+            return true;
+        }
+        let (x, y) = (i % width, i / width);
+        let neighbors = [
+            (x > 0).then(|| i - 1),
+            (x + 1 < width).then_some(i + 1),
+            (y > 0).then(|| i - width),
+            (y + 1 < height).then_some(i + width),
+        ];
+        for j in neighbors.into_iter().flatten() {
+            if !map[j] && !visited[j] {
+                visited[j] = true;
+                stack.push(j);
+            }
+        }
+    }
+    false
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::Pcg32;
+
+    use alloc::{vec, vec::Vec};
+
+    /// Tests that `gen_random_dag` returns forward edges with the expected density.
+    #[test]
+    fn gen_random_dag() {
+        let mut g = Pcg32::new(0x3956_c25b_59f1_11f1, 0x923f_82a4_ab1c_5ed5);
+        for (n, edge_prob) in [(0, 0.5), (1, 0.5), (60, 0.0), (60, 0.3), (60, 1.0)] {
+            let trials = 10;
+            let mut total = 0;
+            for _ in 0..trials {
+                let edges = g.gen_random_dag(n, edge_prob);
+                assert!(edges.iter().all(|&(u, v)| u < v && v < n));
+                assert!(edges.windows(2).all(|w| w[0] < w[1]));
+                total += edges.len();
+            }
+            let expected = edge_prob * (n * n.saturating_sub(1) / 2 * trials) as f64;
+            assert!((total as f64 - expected).abs() <= 0.03 * expected);
+        }
+    }
+
+    /// Tests that `gen_weighted_graph` returns unique edges with weights in range.
+    #[test]
+    fn gen_weighted_graph() {
+        let mut g = Pcg32::new(0x6a09_e667_f3bc_c909, 0xbb67_ae85_84ca_a73c);
+        for (n, edge_prob, max_weight) in [(0, 0.5, 1), (2, 1.0, 1), (50, 0.2, 10), (80, 0.7, 3)] {
+            let edges = g.gen_weighted_graph(n, edge_prob, max_weight);
+            assert!(edges
+                .windows(2)
+                .all(|w| (w[0].0, w[0].1) < (w[1].0, w[1].1)));
+            let mut weights = [0u32; 10];
+            for &(u, v, w) in &edges {
+                assert!(u < v && v < n);
+                assert!((1..=max_weight).contains(&w));
+                weights[w as usize - 1] += 1;
+            }
+            if edges.len() > 100 {
+                assert!(weights[..max_weight as usize].iter().all(|&c| c > 0));
+            }
+            let expected = edge_prob * (n * n.saturating_sub(1) / 2) as f64;
+            assert!((edges.len() as f64 - expected).abs() <= 0.1 * expected);
+        }
+    }
+
+    /// Tests that `gen_maze` produces a deterministic perfect maze.
+    #[test]
+    fn gen_maze() {
+        let g = Pcg32::new(0x19a4_c116_b8d2_d0c8, 0x1e37_6c08_5141_ab53);
+        for (width, height) in [(0, 0), (0, 5), (1, 1), (1, 7), (8, 1), (13, 9), (32, 32)] {
+            let maze = g.clone().gen_maze(width, height);
+            assert_eq!(maze, g.clone().gen_maze(width, height));
+
+            let (cols, rows) = (2 * width + 1, 2 * height + 1);
+            assert_eq!(maze.len(), cols * rows);
+            for x in 0..cols {
+                assert!(maze[x] && maze[(rows - 1) * cols + x]);
+            }
+            for y in 0..rows {
+                assert!(maze[y * cols] && maze[y * cols + cols - 1]);
+            }
+            if width == 0 || height == 0 {
+                assert!(maze.iter().all(|&w| w));
+                continue;
+            }
+
+            // a perfect maze is a spanning tree: all cells connected by `cells - 1` passages
+            let cells = width * height;
+            assert_eq!(maze.iter().filter(|&&w| !w).count(), 2 * cells - 1);
+            let mut seen = vec![false; maze.len()];
+            let mut stack = vec![cols + 1];
+            seen[cols + 1] = true;
+            while let Some(i) = stack.pop() {
+                for j in [i - 1, i + 1, i - cols, i + cols] {
+                    if !maze[j] && !seen[j] {
+                        seen[j] = true;
+                        stack.push(j);
+                    }
+                }
+            }
+            for y in 0..height {
+                for x in 0..width {
+                    assert!(seen[(2 * y + 1) * cols + 2 * x + 1]);
+                }
+            }
+        }
+    }
+
+    /// Tests that `gen_random_tree` returns a valid parent array of a tree rooted at zero.
+    #[test]
+    fn gen_random_tree() {
+        let mut g = Pcg32::new(0x78a5_636f_4317_2f60, 0x84c8_7814_a1f0_ab72);
+        for n in [0, 1, 2, 10, 1000] {
+            let parents = g.gen_random_tree(n);
+            assert_eq!(parents.len(), n);
+            if n == 0 {
+                continue;
+            }
+            assert_eq!(parents[0], 0);
+            assert!(parents.iter().enumerate().skip(1).all(|(i, &p)| p < i));
+
+            // every node reaches the root by following parents
+            for start in 0..n {
+                let (mut i, mut steps) = (start, 0);
+                while i != 0 {
+                    i = parents[i];
+                    steps += 1;
+                }
+                assert!(steps <= start);
+            }
+        }
+
+        assert_ne!(g.gen_random_tree(100), g.gen_random_tree(100));
+    }
+
+    /// Tests that `random_edge_order` returns a deterministic permutation of edge indices.
+    #[test]
+    fn random_edge_order() {
+        let g = Pcg32::new(0x8cc7_0208_1a64_39ec, 0x90be_fffa_2363_1e28);
+        for edges in [0, 1, 2, 10, 1000] {
+            let order = g.clone().random_edge_order(edges);
+            assert_eq!(order, g.clone().random_edge_order(edges));
+            let mut sorted = order.clone();
+            sorted.sort_unstable();
+            assert!(sorted.into_iter().eq(0..edges));
+            if edges >= 10 {
+                assert!(!order.into_iter().eq(0..edges));
+            }
+        }
+    }
+
+    /// Tests that `random_walk` moves along edges and stops at dead ends.
+    #[test]
+    fn random_walk() {
+        let mut g = Pcg32::new(0xb5c0_fbcf_ec4d_3b2f, 0xe9b5_dba5_8189_dbbc);
+        let n = 8;
+        let ring: Vec<Vec<usize>> = (0..n).map(|i| vec![(i + n - 1) % n, (i + 1) % n]).collect();
+        let walk = g.random_walk(&ring, 3, 10_000);
+        assert_eq!(walk.len(), 10_001);
+        assert_eq!(walk[0], 3);
+        let mut forward = 0;
+        for w in walk.windows(2) {
+            assert!(ring[w[0]].contains(&w[1]));
+            forward += (w[1] == (w[0] + 1) % n) as u32;
+        }
+        assert!((forward as f64 / 10_000.0 - 0.5).abs() < 0.02);
+
+        // a path 0 -> 1 -> 2 ending at a dead end
+        let path = [vec![1], vec![2], vec![]];
+        assert_eq!(g.random_walk(&path, 0, 10), [0, 1, 2]);
+        assert_eq!(g.random_walk(&path, 0, 1), [0, 1]);
+        assert_eq!(g.random_walk(&path, 2, 5), [2]);
+    }
+
+    /// Tests that `gen_obstacle_map` connects the corners at the requested density.
+    #[test]
+    fn gen_obstacle_map() {
+        use alloc::collections::VecDeque;
+
+        let mut g = Pcg32::new(0x3956_c25b_f348_b538, 0x59f1_11f1_b605_d019);
+        for (width, height) in [(1, 1), (1, 10), (10, 1), (64, 48)] {
+            for density in [0.0, 0.2, 0.35, 0.6, 1.0] {
+                let map = g.gen_obstacle_map(width, height, density);
+                assert_eq!(map.len(), width * height);
+
+                // breadth-first search from the top-left corner
+                let mut reached = vec![false; map.len()];
+                let mut queue = VecDeque::from([(0, 0)]);
+                reached[0] = true;
+                while let Some((x, y)) = queue.pop_front() {
+                    for (dx, dy) in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
+                        let (nx, ny) = (x + dx, y + dy);
+                        if !(0..width as isize).contains(&nx) || !(0..height as isize).contains(&ny)
+                        {
+                            continue;
+                        }
+                        let j = ny as usize * width + nx as usize;
+                        if !map[j] && !reached[j] {
+                            reached[j] = true;
+                            queue.push_back((nx, ny));
+                        }
+                    }
+                }
+                assert!(reached[map.len() - 1]);
+
+                if width * height > 1000 && density < 0.5 {
+                    let blocked = map.iter().filter(|&&b| b).count() as f64;
+                    assert!((blocked / map.len() as f64 - density).abs() < 0.03);
+                }
+            }
+        }
+    }
+}
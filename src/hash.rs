@@ -0,0 +1,217 @@
+//! Stateless values derived from hashed keys.
+
+use crate::seed::mix64;
+use crate::Pcg32;
+
+impl Pcg32 {
+    /// Returns a deterministic pseudorandom value in `[0, 1)` for the integer lattice point
+    /// `(x, y)` under `seed`.
+    ///
+    /// This is the building block of value noise and similar lattice-based procedural noise: the
+    /// same arguments always yield the same value, while neighboring points yield unrelated
+    /// values. The inputs are hashed into the seeds of a temporary generator.
+    pub fn value_noise_2d(seed: u64, x: i64, y: i64) -> f64 {
+        let key = mix64(mix64(seed ^ x as u64).wrapping_add(y as u64));
+        Self::new(key, seed).next_f64()
+    }
+
+    /// Fills `dest` with a block of random bytes derived from the current state and a `domain`
+    /// label.
+    ///
+    /// The block is generated by a temporary generator on a stream selected by `domain`, so
+    /// blocks for different domains are independent of each other and of the main sequence.
+    /// This generator advances by exactly one step per call regardless of the length of `dest`,
+    /// so that successive calls yield fresh blocks while equal generators reproduce equal blocks
+    /// for equal domains.
+    pub fn fill_bytes_counter(&mut self, domain: u64, dest: &mut [u8]) {
+        let key = self.state;
+        self.generate();
+        Self::new(key, mix64(domain)).fill_bytes(dest);
+    }
+
+    /// Returns a stable bucket index in `0..buckets` for `key` under `salt`, without advancing
+    /// the generator.
+    ///
+    /// The result is a deterministic function of the current state, `salt`, and `key`, so the
+    /// same generator yields a fixed mapping of keys to buckets for each salt, while different
+    /// salts yield independent mappings. This is useful for A/B test assignment and sharding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buckets` is zero.
+    pub fn salted_bucket(&self, salt: u64, key: u64, buckets: u32) -> u32 {
+        assert!(buckets > 0, "buckets must be nonzero");
+        let hashed = mix64(self.state ^ mix64(key));
+        Self::new(hashed, mix64(self.inc ^ salt)).bounded_u32(buckets)
+    }
+
+    /// Returns a stable pseudorandom sort key for `item_id`, without advancing the generator.
+    ///
+    /// The key is the first 64-bit output of a temporary generator seeded from the current state
+    /// and the hashed `item_id`, so sorting items by their keys arranges them in an order that is
+    /// fixed for this generator but unrelated across generators.
+    pub fn order_key(&self, item_id: u64) -> u64 {
+        Self::new(self.state ^ mix64(item_id), self.inc >> 1).next_u64()
+    }
+
+    /// Returns a 32-byte digest of the first `count` outputs of the generator initialized with
+    /// `initstate` and `initseq`.
+    ///
+    /// The outputs are folded round-robin into four 64-bit accumulators through the SplitMix64
+    /// output function, and the accumulators are finally mixed together with `count`. Pinning
+    /// the digest in a test is a compact way for downstream projects to detect any change in the
+    /// generated sequence.
+    pub fn snapshot(initstate: u64, initseq: u64, count: usize) -> [u8; 32] {
+        let mut g = Self::new(initstate, initseq);
+        let mut lanes = [1, 2, 3, 4].map(mix64);
+        for i in 0..count {
+            let lane = &mut lanes[i % 4];
+            *lane = mix64(*lane ^ g.generate() as u64);
+        }
+
+        let acc = lanes
+            .iter()
+            .fold(count as u64, |acc, &lane| mix64(acc ^ lane));
+        let mut digest = [0; 32];
+        for (chunk, lane) in digest.chunks_exact_mut(8).zip(lanes) {
+            chunk.copy_from_slice(&mix64(acc ^ lane).to_le_bytes());
+        }
+        digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    /// Tests that `value_noise_2d` is a deterministic function of its arguments.
+    #[test]
+    fn value_noise_2d() {
+        let mut sum = 0.0;
+        let mut prev = f64::NAN;
+        for seed in [0, 1, 0xdead_beef] {
+            for x in -20..20 {
+                for y in -20..20 {
+                    let v = Pcg32::value_noise_2d(seed, x, y);
+                    assert!((0.0..1.0).contains(&v));
+                    assert_eq!(v, Pcg32::value_noise_2d(seed, x, y));
+                    assert_ne!(v, prev);
+                    assert_ne!(v, Pcg32::value_noise_2d(seed, y, x + 100));
+                    assert_ne!(v, Pcg32::value_noise_2d(seed ^ 1, x, y));
+                    sum += v;
+                    prev = v;
+                }
+            }
+        }
+        assert!((sum / (3 * 40 * 40) as f64 - 0.5).abs() < 0.02);
+
+        let extremes = [i64::MIN, -1, 0, 1, i64::MAX];
+        for (i, &x) in extremes.iter().enumerate() {
+            for &y in &extremes[..i] {
+                assert_ne!(
+                    Pcg32::value_noise_2d(7, x, y),
+                    Pcg32::value_noise_2d(7, y, x)
+                );
+            }
+        }
+    }
+
+    /// Tests that `salted_bucket` is stable for a fixed salt and reshuffles across salts.
+    #[test]
+    fn salted_bucket() {
+        let g = Pcg32::new(0x5cb0_a9dc_bd41_fbd4, 0x76f9_88da_8311_53b5);
+        let h = g.clone();
+        let buckets = 10;
+        let mut freq = [0u32; 10];
+        let mut moved = 0;
+        for key in 0..10_000 {
+            let b = g.salted_bucket(1, key, buckets);
+            assert!(b < buckets);
+            assert_eq!(b, g.salted_bucket(1, key, buckets));
+            freq[b as usize] += 1;
+            moved += (b != g.salted_bucket(2, key, buckets)) as u32;
+        }
+        assert_eq!(g, h);
+        assert!(freq.iter().all(|&f| (f as i32 - 1000).abs() < 100));
+        assert!((moved as f64 / 10_000.0 - 0.9).abs() < 0.02);
+    }
+
+    /// Tests that `salted_bucket` rejects zero buckets.
+    #[test]
+    #[should_panic]
+    fn salted_bucket_zero_buckets() {
+        Pcg32::default().salted_bucket(0, 0, 0);
+    }
+
+    /// Tests that sorting by `order_key` is stable for a fixed generator and reshuffles across
+    /// seeds.
+    #[test]
+    fn order_key() {
+        let g = Pcg32::new(0x0d3c_1b78_9a6f_2e45, 0x4f1e_8a27_c63b_d590);
+        let h = g.clone();
+        let sorted = |g: &Pcg32| {
+            let mut ids: [u64; 100] = core::array::from_fn(|i| i as u64);
+            ids.sort_unstable_by_key(|&id| g.order_key(id));
+            ids
+        };
+
+        let order = sorted(&g);
+        assert_eq!(sorted(&g), order);
+        assert_eq!(g, h);
+        let fixed = order.iter().enumerate().filter(|&(i, &id)| id == i as u64);
+        assert!(fixed.count() < 5);
+
+        let other = sorted(&Pcg32::new(0x0d3c_1b78_9a6f_2e46, 0x4f1e_8a27_c63b_d590));
+        let same = order.iter().zip(other).filter(|&(&a, b)| a == b);
+        assert!(same.count() < 5);
+    }
+
+    /// Tests that `fill_bytes_counter` separates blocks by domain and by position.
+    #[test]
+    fn fill_bytes_counter() {
+        let g = Pcg32::new(0x27b7_0a85_2e1b_2138, 0x4d2c_6dfc_5ac4_2aed);
+        let mut h = g.clone();
+        h.generate();
+
+        let fill = |g: &mut Pcg32, domain| {
+            let mut block = [0u8; 37];
+            g.fill_bytes_counter(domain, &mut block);
+            block
+        };
+        let (mut a, mut b) = (g.clone(), g.clone());
+        assert_eq!(fill(&mut a, 1), fill(&mut b, 1));
+        assert_eq!(a, h);
+        assert_eq!(b, h);
+        assert_ne!(fill(&mut g.clone(), 1), fill(&mut g.clone(), 2));
+        assert_ne!(fill(&mut a, 1), fill(&mut g.clone(), 1));
+
+        // the block is independent of the main sequence
+        let mut main = [0u8; 37];
+        g.clone().fill_bytes(&mut main);
+        assert_ne!(fill(&mut g.clone(), 0), main);
+
+        let mut c = g.clone();
+        c.fill_bytes_counter(1, &mut []);
+        assert_eq!(c, h);
+    }
+
+    /// Tests that `snapshot` of the demo seed is pinned and sensitive to its arguments.
+    #[test]
+    fn snapshot() {
+        // `(42, 54)` is the seed used by the demo program of the official library
+        assert_eq!(Pcg32::new(42, 54).generate(), 0xa15c_02b7);
+        let base = Pcg32::snapshot(42, 54, 1000);
+        assert_eq!(
+            base,
+            [
+                0xa0, 0x99, 0xe5, 0x98, 0xe6, 0x13, 0x5a, 0x72, 0x57, 0x81, 0x62, 0x34, 0x1d, 0xee,
+                0xa4, 0xd8, 0x77, 0x35, 0xc3, 0xa0, 0xc6, 0x28, 0x2f, 0x13, 0x09, 0xa7, 0xcf, 0x60,
+                0xbc, 0xf2, 0x7c, 0x0d,
+            ]
+        );
+        assert_ne!(base, Pcg32::snapshot(42, 54, 999));
+        assert_ne!(base, Pcg32::snapshot(43, 54, 1000));
+        assert_ne!(base, Pcg32::snapshot(42, 55, 1000));
+        assert_ne!(Pcg32::snapshot(42, 54, 0), Pcg32::snapshot(42, 54, 1));
+    }
+}
@@ -0,0 +1,151 @@
+//! Hexadecimal text representation of the generator.
+
+use crate::Pcg32;
+
+use core::fmt;
+
+/// An error returned by [`Pcg32::from_hex`] when the input is not a valid hex representation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is not exactly 32 bytes long; holds the actual length in bytes.
+    InvalidLength(usize),
+
+    /// The input contains a character other than a hexadecimal digit.
+    InvalidDigit {
+        /// The byte offset of the offending character.
+        index: usize,
+        /// The offending character.
+        found: char,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(len) => {
+                write!(
+                    f,
+                    "invalid length: expected 32 hex digits, found {} bytes",
+                    len
+                )
+            }
+            Self::InvalidDigit { index, found } => {
+                write!(f, "invalid hex digit {:?} at byte {}", found, index)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+impl Pcg32 {
+    /// Encodes the generator as 32 lowercase hexadecimal digits in ASCII.
+    ///
+    /// The digits spell the bytes of [`to_bytes`](Self::to_bytes) in order, so the text is
+    /// stable across versions and platforms. The result can be viewed as a `&str` with
+    /// `core::str::from_utf8(&g.to_hex()).unwrap()` and parsed back by
+    /// [`from_hex`](Self::from_hex), which makes it handy for logging reproducible states.
+    pub fn to_hex(&self) -> [u8; 32] {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+        let mut hex = [0; 32];
+        for (pair, byte) in hex.chunks_exact_mut(2).zip(self.to_bytes()) {
+            pair[0] = DIGITS[(byte >> 4) as usize];
+            pair[1] = DIGITS[(byte & 0xf) as usize];
+        }
+        hex
+    }
+
+    /// Decodes a generator from the 32 hexadecimal digits written by
+    /// [`to_hex`](Self::to_hex).
+    ///
+    /// Both lowercase and uppercase digits are accepted. As with
+    /// [`from_state`](Self::from_state), an even increment is forced to be odd.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `s` is not exactly 32 bytes long or contains a character other than
+    /// a hexadecimal digit.
+    pub fn from_hex(s: &str) -> Result<Self, ParseError> {
+        if s.len() != 32 {
+            return Err(ParseError::InvalidLength(s.len()));
+        }
+        if let Some((index, found)) = s.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+            return Err(ParseError::InvalidDigit { index, found });
+        }
+
+        let mut bytes = [0; 16];
+        for (byte, pair) in bytes.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+            let digit = |c: u8| (c as char).to_digit(16).unwrap() as u8;
+            *byte = (digit(pair[0]) << 4) | digit(pair[1]);
+        }
+        Ok(Self::from_bytes(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParseError;
+    use crate::Pcg32;
+
+    use std::string::ToString;
+
+    /// Tests that `to_hex` and `from_hex` round-trip a generator mid-sequence.
+    #[test]
+    fn round_trip() {
+        let mut g = Pcg32::new(0x2b7e_1516_28ae_d2a6, 0xabf7_1588_09cf_4f3c);
+        for _ in 0..21 {
+            g.generate();
+        }
+        let hex = g.to_hex();
+        let s = core::str::from_utf8(&hex).unwrap();
+        assert!(s.bytes().all(|c| matches!(c, b'0'..=b'9' | b'a'..=b'f')));
+        let mut h = Pcg32::from_hex(s).unwrap();
+        assert_eq!(h, g);
+        for _ in 0..100 {
+            assert_eq!(h.generate(), g.generate());
+        }
+
+        let h = Pcg32::from_hex("2A000000000000003600000000000000").unwrap();
+        assert_eq!(h, Pcg32::from_state(42, 55));
+        assert_eq!(&h.to_hex(), b"2a000000000000003700000000000000");
+    }
+
+    /// Tests that `from_hex` rejects malformed inputs with descriptive errors.
+    #[test]
+    fn from_hex_malformed() {
+        let e = Pcg32::from_hex("0123").unwrap_err();
+        assert_eq!(e, ParseError::InvalidLength(4));
+        assert_eq!(
+            e.to_string(),
+            "invalid length: expected 32 hex digits, found 4 bytes"
+        );
+        assert_eq!(
+            Pcg32::from_hex(&"0".repeat(33)),
+            Err(ParseError::InvalidLength(33))
+        );
+
+        let e = Pcg32::from_hex("0123456789abcdef0123456789abcdeg").unwrap_err();
+        assert_eq!(
+            e,
+            ParseError::InvalidDigit {
+                index: 31,
+                found: 'g'
+            }
+        );
+        assert_eq!(e.to_string(), "invalid hex digit 'g' at byte 31");
+        assert_eq!(
+            Pcg32::from_hex("0x23456789abcdef0123456789abcdef"),
+            Err(ParseError::InvalidDigit {
+                index: 1,
+                found: 'x'
+            })
+        );
+        assert_eq!(
+            Pcg32::from_hex("é123456789abcdef0123456789abcde"),
+            Err(ParseError::InvalidDigit {
+                index: 0,
+                found: 'é'
+            })
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Shuffled but unique identifiers.
+
+use crate::seed::mix64;
+use crate::Pcg32;
+
+/// A generator of distinct 64-bit IDs in a shuffled order.
+///
+/// Each ID is obtained by passing a counter through a four-round Feistel network keyed by the
+/// generator it is created from. Since the network is a permutation of the 64-bit integers, no
+/// ID repeats until the counter wraps around after `2^64` calls, while successive IDs look
+/// unrelated to each other.
+///
+/// ```rust
+/// let mut g = pcg32::Pcg32::default();
+/// let mut ids = pcg32::SequentialIds::new(&mut g);
+///
+/// let (a, b) = (ids.next_id(), ids.next_id());
+/// assert_ne!(a, b);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SequentialIds {
+    keys: [u32; 4],
+    counter: u64,
+}
+
+impl SequentialIds {
+    /// Creates an ID generator whose round keys are drawn from `rng`.
+    ///
+    /// This consumes four steps of `rng`, so generators created from equal states yield equal
+    /// sequences of IDs.
+    pub fn new(rng: &mut Pcg32) -> Self {
+        Self {
+            keys: core::array::from_fn(|_| rng.generate()),
+            counter: 0,
+        }
+    }
+
+    /// Returns the next ID, which differs from all IDs returned before within the period of
+    /// `2^64` calls.
+    pub fn next_id(&mut self) -> u64 {
+        let (mut left, mut right) = ((self.counter >> 32) as u32, self.counter as u32);
+        for key in self.keys {
+            let f = mix64((right as u64) << 32 | key as u64) as u32;
+            (left, right) = (right, left ^ f);
+        }
+        self.counter = self.counter.wrapping_add(1);
+        (left as u64) << 32 | right as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SequentialIds;
+    use crate::Pcg32;
+
+    /// Tests that `SequentialIds` returns distinct and deterministic IDs.
+    #[test]
+    fn sequential_ids() {
+        let mut g = Pcg32::new(0x9bdc_06a7_25c7_1235, 0xc19b_f174_cf69_2694);
+        let mut h = g.clone();
+        let mut ids = SequentialIds::new(&mut g);
+        let mut batch: [u64; 10_000] = core::array::from_fn(|_| ids.next_id());
+
+        let mut same = SequentialIds::new(&mut h);
+        assert!(batch.iter().all(|&id| id == same.next_id()));
+        assert_eq!(g, h);
+        assert_ne!(SequentialIds::new(&mut g).next_id(), batch[0]);
+
+        // the IDs are not simply increasing
+        assert!(batch.windows(2).any(|w| w[0] > w[1]));
+        assert!(batch.iter().any(|&id| id >= 1 << 63));
+
+        batch.sort_unstable();
+        assert!(batch.windows(2).all(|w| w[0] != w[1]));
+    }
+}
@@ -0,0 +1,53 @@
+//! Adapters to the standard I/O traits.
+
+use crate::Pcg32;
+
+use std::io;
+
+impl Pcg32 {
+    /// Returns an adapter that produces an endless stream of random bytes through
+    /// [`std::io::Read`].
+    ///
+    /// Every `read` call fills the entire buffer with the little-endian bytes of successive
+    /// outputs, discarding the unused bytes of the final output of each call. This is handy to
+    /// feed random input to parsers, compressors, and other `Read` consumers.
+    pub fn as_read(&mut self) -> impl io::Read + '_ {
+        Reader(self)
+    }
+}
+
+/// A [`std::io::Read`] adapter borrowing a generator.
+struct Reader<'a>(&'a mut Pcg32);
+
+impl io::Read for Reader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.fill_bytes(buf);
+        Ok(buf.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    /// Tests that bytes read through `as_read` match `fill_bytes` on an equal generator.
+    #[test]
+    fn as_read() {
+        use std::io::Read;
+
+        let mut g = Pcg32::new(0x72be_5d74_f27b_896f, 0x80de_b1fe_3b16_96b1);
+        let mut h = g.clone();
+        for len in [0, 1, 3, 4, 5, 64, 1023] {
+            let mut expected = [0u8; 1023];
+            h.fill_bytes(&mut expected[..len]);
+            let mut actual = [0u8; 1023];
+            g.as_read().read_exact(&mut actual[..len]).unwrap();
+            assert_eq!(actual[..len], expected[..len]);
+            assert_eq!(g, h);
+        }
+
+        let mut buf = std::vec::Vec::new();
+        g.as_read().take(100).read_to_end(&mut buf).unwrap();
+        assert_eq!(buf.len(), 100);
+    }
+}
@@ -0,0 +1,114 @@
+//! Iterator adapters over the output stream.
+
+use crate::Pcg32;
+
+use core::iter::FusedIterator;
+
+impl Pcg32 {
+    /// Returns an endless iterator over the outputs of [`generate`](Self::generate).
+    ///
+    /// The iterator borrows the generator mutably, so the generator resumes right after the
+    /// last output consumed through the iterator, e.g., `g.iter().take(10)` advances `g` by
+    /// exactly ten steps.
+    pub fn iter(&mut self) -> impl FusedIterator<Item = u32> + '_ {
+        Iter(self)
+    }
+
+    /// Returns an endless iterator over random bytes.
+    ///
+    /// Each output of [`generate`](Self::generate) is buffered and handed out as four bytes in
+    /// little-endian order before the next one is drawn, so the bytes agree with
+    /// [`fill_bytes`](Self::fill_bytes) and the generator advances by one step per four bytes
+    /// started. Any buffered bytes left unconsumed are discarded when the iterator is dropped.
+    pub fn bytes(&mut self) -> impl FusedIterator<Item = u8> + '_ {
+        Bytes {
+            rng: self,
+            buf: [0; 4],
+            pos: 4,
+        }
+    }
+}
+
+/// An endless [`Iterator`] over the outputs of a borrowed generator.
+struct Iter<'a>(&'a mut Pcg32);
+
+impl Iterator for Iter<'_> {
+    type Item = u32;
+
+    #[inline]
+    fn next(&mut self) -> Option<u32> {
+        Some(self.0.generate())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for Iter<'_> {}
+
+/// An endless [`Iterator`] over the bytes of the outputs of a borrowed generator.
+struct Bytes<'a> {
+    rng: &'a mut Pcg32,
+    buf: [u8; 4],
+    pos: usize,
+}
+
+impl Iterator for Bytes<'_> {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        if self.pos == self.buf.len() {
+            self.buf = self.rng.generate().to_le_bytes();
+            self.pos = 0;
+        }
+        self.pos += 1;
+        Some(self.buf[self.pos - 1])
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for Bytes<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    use alloc::vec::Vec;
+
+    /// Tests that `iter` yields the outputs of `generate` and advances the generator.
+    #[test]
+    fn iter() {
+        let mut g = Pcg32::new(0x3c6e_f372_fe94_f82b, 0xa54f_f53a_5f1d_36f1);
+        let mut h = g.clone();
+        let expected = [h.generate(), h.generate(), h.generate(), h.generate()];
+        assert_eq!(g.iter().take(4).collect::<Vec<_>>(), expected);
+        assert_eq!(g, h);
+        assert_eq!(g.iter().size_hint(), (usize::MAX, None));
+        assert_eq!(g.iter().nth(2), h.iter().nth(2));
+        assert_eq!(g, h);
+    }
+
+    /// Tests that `bytes` matches `fill_bytes` and draws once per four bytes.
+    #[test]
+    fn bytes() {
+        let mut g = Pcg32::new(0x510e_527f_ade6_82d1, 0x9b05_688c_2b3e_6c1f);
+        let mut h = g.clone();
+        let mut expected = [0u8; 8];
+        h.fill_bytes(&mut expected);
+        assert_eq!(g.bytes().take(8).collect::<Vec<_>>(), expected);
+        assert_eq!(g, h);
+
+        let mut expected = [0u8; 4];
+        h.fill_bytes(&mut expected);
+        assert_eq!(g.bytes().take(1).collect::<Vec<_>>(), expected[..1]);
+        assert_eq!(g, h);
+        assert_eq!(g.bytes().size_hint(), (usize::MAX, None));
+    }
+}
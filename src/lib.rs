@@ -11,13 +11,66 @@
 //! assert_eq!(g.generate(), 0x7e39_20e2);
 //! ```
 //!
-//! This crate is `no_std` compatible.
+//! This crate is `no_std` compatible; all crate features below are opt-in.
+//!
+//! ## Crate features
+//!
+//! - `std`: enables methods that rely on floating-point math from the standard library.
+//!   Implies `alloc`.
+//! - `alloc`: enables methods that return heap-allocated collections.
+//! - `getrandom`: enables `Pcg32::from_entropy` to seed a generator from the random number
+//!   generator of the operating system via the `getrandom` crate.
+//! - `serde`: implements `Serialize` and `Deserialize` of the `serde` crate for `Pcg32`.
 
 #![no_std]
 
+#[cfg(any(feature = "alloc", test))]
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+mod decay;
+mod dist;
+mod fixture;
+mod geom;
+mod graph;
+mod hash;
+mod hex;
+mod ids;
+#[cfg(feature = "std")]
+mod io;
+mod iter;
+#[cfg(feature = "std")]
+mod linalg;
+#[cfg(feature = "std")]
+mod noise;
+mod sampling;
+mod schedule;
+mod seed;
+mod seq;
+#[cfg(feature = "serde")]
+mod serialize;
+mod uniform;
+
+pub use decay::DecayingBool;
+pub use hex::ParseError;
+pub use ids::SequentialIds;
+#[cfg(feature = "alloc")]
+pub use seq::shuffle_keyed;
+
 /// PCG32 multiplier.
 const MUL: u64 = 6364136223846793005;
 
+/// Modular multiplicative inverse of `MUL` modulo 2^64.
+const MUL_INV: u64 = 13877824140714322085;
+
+/// Computes the output permutation (XSH RR) of a state.
+#[inline]
+const fn output(s: u64) -> u32 {
+    let xorshifted = (((s >> 18) ^ s) >> 27) as u32;
+    xorshifted.rotate_right((s >> 59) as u32)
+}
+
 /// A PCG32 random number generator.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Pcg32 {
@@ -46,13 +99,226 @@ impl Pcg32 {
         }
     }
 
+    /// Restores a generator from its raw internal `state` and increment `inc`.
+    ///
+    /// Unlike [`new`](Self::new), this stores the values verbatim without the seeding transform,
+    /// except that `inc` is forced to be odd because an even increment would break the full
+    /// period of the generator. This is meant for deserializing a saved generator, not for
+    /// seeding a new one: use `new` to derive a generator from arbitrary seed values.
+    #[inline]
+    pub const fn from_state(state: u64, inc: u64) -> Self {
+        Self {
+            state,
+            inc: inc | 1,
+        }
+    }
+
+    /// Returns the raw internal state, which can be passed to [`from_state`](Self::from_state)
+    /// together with [`increment`](Self::increment) to restore the generator.
+    #[inline]
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Returns the raw increment, which selects the output sequence and is always odd.
+    #[inline]
+    pub const fn increment(&self) -> u64 {
+        self.inc
+    }
+
+    /// Encodes the generator into 16 bytes: the raw state followed by the raw increment, each
+    /// in little-endian byte order.
+    ///
+    /// This wire format is stable across versions and platforms and is decoded by
+    /// [`from_bytes`](Self::from_bytes).
+    pub const fn to_bytes(&self) -> [u8; 16] {
+        Self::join_words(self.state, self.inc)
+    }
+
+    /// Decodes a generator from the 16 bytes written by [`to_bytes`](Self::to_bytes).
+    ///
+    /// As with [`from_state`](Self::from_state), an even increment is forced to be odd.
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        let (state, inc) = Self::split_words(bytes);
+        Self::from_state(state, inc)
+    }
+
+    /// Encodes the generator into 16 bytes: the raw state followed by the increment shifted
+    /// right by one bit, each in little-endian byte order.
+    ///
+    /// The low bit of the increment is always one and is thus dropped, which leaves the most
+    /// significant bit of the second word clear. Callers packing many states can use that spare
+    /// bit as a flag of their own, as [`from_bytes_compact`](Self::from_bytes_compact) ignores
+    /// it. This wire format is stable across versions and platforms.
+    pub const fn to_bytes_compact(&self) -> [u8; 16] {
+        Self::join_words(self.state, self.inc >> 1)
+    }
+
+    /// Decodes a generator from the 16 bytes written by
+    /// [`to_bytes_compact`](Self::to_bytes_compact), restoring the low bit of the increment.
+    pub const fn from_bytes_compact(bytes: [u8; 16]) -> Self {
+        let (state, inc) = Self::split_words(bytes);
+        Self {
+            state,
+            inc: (inc << 1) | 1,
+        }
+    }
+
+    /// Writes two `u64` words into 16 bytes in little-endian byte order.
+    const fn join_words(lo: u64, hi: u64) -> [u8; 16] {
+        let mut bytes = [0; 16];
+        let (lo, hi) = (lo.to_le_bytes(), hi.to_le_bytes());
+        let mut i = 0;
+        while i < 8 {
+            bytes[i] = lo[i];
+            bytes[8 + i] = hi[i];
+            i += 1;
+        }
+        bytes
+    }
+
+    /// Reads two little-endian `u64` words from 16 bytes.
+    const fn split_words(bytes: [u8; 16]) -> (u64, u64) {
+        let (mut lo, mut hi) = ([0; 8], [0; 8]);
+        let mut i = 0;
+        while i < 8 {
+            lo[i] = bytes[i];
+            hi[i] = bytes[8 + i];
+            i += 1;
+        }
+        (u64::from_le_bytes(lo), u64::from_le_bytes(hi))
+    }
+
     /// Generates a pseudorandom uniformly distributed 32-bit unsigned integer.
     #[inline]
     pub fn generate(&mut self) -> u32 {
         let s = self.state;
         self.state = s.wrapping_mul(MUL).wrapping_add(self.inc);
-        let xorshifted = (((s >> 18) ^ s) >> 27) as u32;
-        xorshifted.rotate_right((s >> 59) as u32)
+        output(s)
+    }
+
+    /// Moves the generator exactly one step backward and returns the output at the restored
+    /// position, i.e., the value returned by the last call to [`generate`](Self::generate).
+    ///
+    /// This undoes a `generate` call in constant time by applying the modular inverse of the
+    /// multiplier.
+    #[inline]
+    pub fn unstep(&mut self) -> u32 {
+        self.state = self.state.wrapping_sub(self.inc).wrapping_mul(MUL_INV);
+        output(self.state)
+    }
+
+    /// Generates a pseudorandom uniformly distributed 64-bit unsigned integer.
+    ///
+    /// The result combines two successive outputs of [`generate`](Self::generate): the first
+    /// becomes the high 32 bits and the second the low 32 bits, i.e., it equals
+    /// `((g.generate() as u64) << 32) | g.generate() as u64` evaluated left to right. This
+    /// consumes exactly two steps of the underlying stream, so mixing it with other calls keeps
+    /// the sequence reproducible.
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        let hi = self.generate() as u64;
+        (hi << 32) | self.generate() as u64
+    }
+
+    /// Fills `dst` with pseudorandom bytes.
+    ///
+    /// The slice is filled with the little-endian bytes of successive outputs of
+    /// [`generate`](Self::generate). If the length is not a multiple of four, one more output is
+    /// drawn for the trailing 1–3 bytes, of which only the low bytes are used. Thus, this
+    /// consumes exactly `dst.len().div_ceil(4)` steps, and none for an empty slice.
+    pub fn fill_bytes(&mut self, dst: &mut [u8]) {
+        let mut chunks = dst.chunks_exact_mut(4);
+        for chunk in &mut chunks {
+            chunk.copy_from_slice(&self.generate().to_le_bytes());
+        }
+        let rem = chunks.into_remainder();
+        if !rem.is_empty() {
+            let n = rem.len();
+            rem.copy_from_slice(&self.generate().to_le_bytes()[..n]);
+        }
+    }
+
+    /// Returns an iterator that replays `len` outputs of the generator initialized with
+    /// `initstate` and `initseq`, starting from the `from`-th (zero-based) output.
+    ///
+    /// The starting position is reached by jumping ahead in `O(log from)` time, so this provides
+    /// random access to any recorded position in the sequence.
+    pub fn replay(
+        initstate: u64,
+        initseq: u64,
+        from: u64,
+        len: usize,
+    ) -> impl Iterator<Item = u32> {
+        let mut g = Self::new(initstate, initseq);
+        g.advance(from);
+        (0..len).map(move |_| g.generate())
+    }
+
+    /// Advances the generator by `delta` steps in `O(log delta)` time.
+    ///
+    /// The result is exactly the same as discarding `delta` outputs of
+    /// [`generate`](Self::generate), which makes it easy to split a single stream into
+    /// non-overlapping blocks for parallel workers. `delta == 0` leaves the generator unchanged.
+    ///
+    /// The multiplier and increment of the LCG are combined by repeated squaring as described
+    /// in Brown, "Random Number Generation with Arbitrary Stride," Trans. Am. Nucl. Soc. (1994).
+    pub fn advance(&mut self, mut delta: u64) {
+        let (mut acc_mult, mut acc_plus) = (1u64, 0u64);
+        let (mut cur_mult, mut cur_plus) = (MUL, self.inc);
+        while delta > 0 {
+            if delta & 1 != 0 {
+                acc_mult = acc_mult.wrapping_mul(cur_mult);
+                acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+            }
+            cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+            cur_mult = cur_mult.wrapping_mul(cur_mult);
+            delta >>= 1;
+        }
+        self.state = acc_mult.wrapping_mul(self.state).wrapping_add(acc_plus);
+    }
+
+    /// Discards the next `n` outputs in `O(log n)` time.
+    ///
+    /// Since each call to [`generate`](Self::generate) performs exactly one step, this is the
+    /// same as [`advance`](Self::advance), provided so that callers resuming a simulation need
+    /// not reason about the underlying state transitions.
+    pub fn skip(&mut self, n: u64) {
+        self.advance(n);
+    }
+
+    /// Moves the generator back by `delta` steps in `O(log delta)` time.
+    ///
+    /// This exactly undoes `delta` prior calls to [`generate`](Self::generate). Since the
+    /// generator has a period of `2^64`, it is implemented as advancing by `2^64 - delta` steps,
+    /// so retreating past the starting point wraps around cleanly.
+    pub fn retreat(&mut self, delta: u64) {
+        self.advance(delta.wrapping_neg());
+    }
+
+    /// Returns the number of steps to [`advance`](Self::advance) this generator by to reach the
+    /// state of `other`, or `None` if the two generators are on different sequences.
+    ///
+    /// The distance is determined bit by bit from the least significant bit of the state, as in
+    /// the official C++ library, so this takes at most 64 iterations.
+    pub fn distance(&self, other: &Self) -> Option<u64> {
+        if self.inc != other.inc {
+            return None;
+        }
+        let (mut cur_mult, mut cur_plus) = (MUL, self.inc);
+        let mut state = self.state;
+        let mut distance = 0;
+        let mut bit = 1u64;
+        while state != other.state {
+            if (state ^ other.state) & bit != 0 {
+                state = state.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+                distance |= bit;
+            }
+            bit <<= 1;
+            cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+            cur_mult = cur_mult.wrapping_mul(cur_mult);
+        }
+        Some(distance)
     }
 
     /// Alias to `generate` for backward compatibility.
@@ -271,4 +537,248 @@ mod tests {
             }
         }
     }
+
+    /// Tests that `replay` reproduces a subsequence of the generator started from the seeds.
+    #[test]
+    fn replay() {
+        let (initstate, initseq) = (0x2d35_8dcc_aa6c_78a5, 0x8bb8_4b93_962e_acc9);
+        for (from, len) in [(0, 0), (0, 16), (1, 1), (37, 100), (1000, 50), (65_537, 8)] {
+            let mut g = Pcg32::new(initstate, initseq);
+            for _ in 0..from {
+                g.generate();
+            }
+            let mut count = 0;
+            for e in Pcg32::replay(initstate, initseq, from, len) {
+                assert_eq!(e, g.generate());
+                count += 1;
+            }
+            assert_eq!(count, len);
+        }
+    }
+
+    /// Tests that `unstep` returns the last generated value and restores the prior state.
+    #[test]
+    fn unstep() {
+        assert_eq!(super::MUL.wrapping_mul(super::MUL_INV), 1);
+
+        let mut g = Pcg32::new(0xcbbb_9d5d_c105_9ed8, 0x629a_292a_367c_d507);
+        let history: [_; 64] = core::array::from_fn(|_| (g.clone(), g.generate()));
+        for (prev, value) in history.into_iter().rev() {
+            assert_eq!(g.unstep(), value);
+            assert_eq!(g, prev);
+        }
+    }
+
+    /// Tests that `next_u64` puts the first of two outputs in the high half.
+    #[test]
+    fn next_u64() {
+        for (initstate, initseq) in [(0, 0), (42, 54), (0x13198a2e03707344, 0xa4093822299f31d0)] {
+            let mut g = Pcg32::new(initstate, initseq);
+            let mut h = g.clone();
+            for _ in 0..16 {
+                let expected = ((h.generate() as u64) << 32) | h.generate() as u64;
+                assert_eq!(g.next_u64(), expected);
+            }
+            assert_eq!(g, h);
+        }
+    }
+
+    /// Tests that `fill_bytes` writes little-endian words and draws once per started word.
+    #[test]
+    fn fill_bytes() {
+        let mut g = Pcg32::new(0x452821e638d01377, 0xbe5466cf34e90c6c);
+        let mut h = g.clone();
+
+        let mut buf = [0u8; 16];
+        g.fill_bytes(&mut buf);
+        for chunk in buf.chunks(4) {
+            assert_eq!(chunk, h.generate().to_le_bytes());
+        }
+        assert_eq!(g, h);
+
+        g.fill_bytes(&mut []);
+        assert_eq!(g, h);
+
+        for len in 1..=11 {
+            let mut buf = [0u8; 11];
+            g.fill_bytes(&mut buf[..len]);
+            let mut expected = [0u8; 12];
+            for chunk in expected[..len.div_ceil(4) * 4].chunks_mut(4) {
+                chunk.copy_from_slice(&h.generate().to_le_bytes());
+            }
+            assert_eq!(buf[..len], expected[..len]);
+            assert!(buf[len..].iter().all(|&b| b == 0));
+            assert_eq!(g, h);
+        }
+    }
+
+    /// Tests that `advance` is equivalent to discarding outputs.
+    #[test]
+    fn advance() {
+        let mut g = Pcg32::new(0x6a09_e667_f3bc_c908, 0x3c6e_f372_fe94_f82b);
+        let mut h = g.clone();
+        g.advance(0);
+        assert_eq!(g, h);
+
+        for delta in [1, 2, 3, 1000, 12_345] {
+            g.advance(delta);
+            for _ in 0..delta {
+                h.generate();
+            }
+            assert_eq!(g, h);
+            assert_eq!(g.generate(), h.generate());
+        }
+
+        // the period is `2^64`
+        let mut h = g.clone();
+        g.advance(u64::MAX);
+        g.generate();
+        assert_eq!(g, h);
+        assert_eq!(g.generate(), h.generate());
+    }
+
+    /// Tests that `retreat` rewinds the generator to replay the same outputs.
+    #[test]
+    fn retreat() {
+        let mut g = Pcg32::new(0xbb67_ae85_84ca_a73b, 0xa54f_f53a_5f1d_36f1);
+        let h = g.clone();
+        let first: [u32; 10] = core::array::from_fn(|_| g.generate());
+        g.retreat(10);
+        assert_eq!(g, h);
+        let second: [u32; 10] = core::array::from_fn(|_| g.generate());
+        assert_eq!(first, second);
+
+        g.retreat(0);
+        g.retreat(3);
+        assert_eq!(g.generate(), first[7]);
+
+        // retreating before the starting point wraps around the period
+        let mut g = h.clone();
+        g.retreat(5);
+        g.advance(5);
+        assert_eq!(g, h);
+    }
+
+    /// Tests that `distance` returns the number of steps between generators on a sequence.
+    #[test]
+    fn distance() {
+        let g = Pcg32::new(0x510e_527f_ade6_82d1, 0x9b05_688c_2b3e_6c1f);
+        let mut h = g.clone();
+        assert_eq!(g.distance(&h), Some(0));
+        for _ in 0..37 {
+            h.generate();
+        }
+        assert_eq!(g.distance(&h), Some(37));
+        assert_eq!(h.distance(&g), Some(37u64.wrapping_neg()));
+
+        for delta in [1, 1 << 20, 0xdead_beef_cafe, u64::MAX] {
+            let mut h = g.clone();
+            h.advance(delta);
+            assert_eq!(g.distance(&h), Some(delta));
+        }
+
+        let other = Pcg32::new(0x510e_527f_ade6_82d1, 0x1f83_d9ab_fb41_bd6b);
+        assert_eq!(g.distance(&other), None);
+    }
+
+    /// Tests that `skip` discards the requested number of outputs.
+    #[test]
+    fn skip() {
+        let mut g = Pcg32::new(0x3956_c25b_f348_b538, 0x923f_82a4_af19_4f9b);
+        let mut h = g.clone();
+        g.skip(7);
+        let eighth = (0..8).map(|_| h.generate()).last();
+        assert_eq!(Some(g.generate()), eighth);
+        assert_eq!(g, h);
+
+        g.skip(0);
+        assert_eq!(g, h);
+    }
+
+    /// Tests that `from_state` restores the raw fields of a generator.
+    #[test]
+    fn from_state() {
+        let mut g = Pcg32::new(0x7137_4491_b5c0_fbcf, 0xe9b5_dba5_3956_c25b);
+        g.generate();
+        let mut h = Pcg32::from_state(g.state(), g.increment());
+        assert_eq!(h, g);
+        assert_eq!(h.generate(), g.generate());
+
+        let h = Pcg32::from_state(42, 54);
+        assert_eq!((h.state(), h.increment()), (42, 55));
+        assert_eq!(Pcg32::from_state(42, 55), h);
+    }
+
+    /// Tests that the raw fields read by the getters restore the subsequent sequence.
+    #[test]
+    fn state_and_increment() {
+        let mut g = Pcg32::new(0x1b87_3593_cc9e_2d51, 0x85eb_ca6b_c2b2_ae35);
+        for _ in 0..5 {
+            g.generate();
+        }
+        let (state, inc) = (g.state(), g.increment());
+        assert_eq!(inc & 1, 1);
+
+        let mut h = Pcg32::from_state(state, inc);
+        for _ in 0..100 {
+            assert_eq!(h.generate(), g.generate());
+        }
+    }
+
+    /// Tests that `to_bytes` and `from_bytes` round-trip a generator in a pinned byte layout.
+    #[test]
+    fn to_bytes() {
+        let mut g = Pcg32::new(0x5be0_cd19_137e_2179, 0x1f83_d9ab_fb41_bd6b);
+        for _ in 0..9 {
+            g.generate();
+        }
+        let bytes = g.to_bytes();
+        assert_eq!(bytes[..8], g.state().to_le_bytes());
+        assert_eq!(bytes[8..], g.increment().to_le_bytes());
+        let mut h = Pcg32::from_bytes(bytes);
+        assert_eq!(h, g);
+        for _ in 0..100 {
+            assert_eq!(h.generate(), g.generate());
+        }
+
+        let mut bytes = [0; 16];
+        bytes[0] = 0x2a;
+        bytes[8] = 0x36;
+        assert_eq!(Pcg32::from_bytes(bytes), Pcg32::from_state(42, 55));
+    }
+
+    /// Tests that `to_bytes_compact` and `from_bytes_compact` round-trip a generator.
+    #[test]
+    fn to_bytes_compact() {
+        let mut g = Pcg32::new(0x6a09_e667_f3bc_c908, u64::MAX);
+        for _ in 0..13 {
+            g.generate();
+        }
+        let bytes = g.to_bytes_compact();
+        assert_eq!(bytes[..8], g.state().to_le_bytes());
+        assert_eq!(bytes[8..], (g.increment() >> 1).to_le_bytes());
+        assert_eq!(bytes[15] & 0x80, 0);
+        let mut h = Pcg32::from_bytes_compact(bytes);
+        assert_eq!(h, g);
+        for _ in 0..100 {
+            assert_eq!(h.generate(), g.generate());
+        }
+
+        // the low bit of the stream selector survives for an even `initseq`
+        for initseq in [0, 2, 0x8000_0000_0000_0000] {
+            let g = Pcg32::new(1, initseq);
+            assert_eq!(Pcg32::from_bytes_compact(g.to_bytes_compact()), g);
+        }
+        let g = Pcg32::new(1, 2);
+        assert_eq!(g.increment(), 0x5);
+        assert_eq!(g.to_bytes_compact()[8..], 0x2u64.to_le_bytes());
+
+        // the spare most significant bit is ignored
+        let mut flagged = bytes;
+        flagged[15] |= 0x80;
+        assert_eq!(
+            Pcg32::from_bytes_compact(flagged),
+            Pcg32::from_bytes_compact(bytes)
+        );
+    }
 }
@@ -0,0 +1,263 @@
+//! Random matrices and multivariate samples.
+//!
+//! Lower-triangular matrices are packed row by row, so that element `(i, j)` with `j <= i` is
+//! stored at index `i * (i + 1) / 2 + j`.
+
+use crate::Pcg32;
+
+use alloc::vec::Vec;
+
+impl Pcg32 {
+    /// Generates a random lower-triangular Cholesky factor `L` of a `dim` × `dim` correlation
+    /// matrix, packed row by row.
+    ///
+    /// Each row is a random direction of unit norm with a positive diagonal element, so that
+    /// `L * Lᵀ` is a valid (positive definite) correlation matrix with a unit diagonal. The
+    /// factor can be passed to [`next_multivariate_normal`](Self::next_multivariate_normal) to
+    /// draw correlated normal samples.
+    pub fn gen_correlation_cholesky(&mut self, dim: usize) -> Vec<f64> {
+        let mut chol = Vec::with_capacity(dim * (dim + 1) / 2);
+        for i in 0..dim {
+            let row = chol.len();
+            chol.extend((0..=i).map(|_| self.next_standard_normal()));
+            while chol[row + i] == 0.0 {
+                chol[row + i] = self.next_standard_normal();
+            }
+            chol[row + i] = chol[row + i].abs();
+            let norm = chol[row..].iter().map(|x| x * x).sum::<f64>().sqrt();
+            chol[row..].iter_mut().for_each(|x| *x /= norm);
+        }
+        chol
+    }
+
+    /// Generates a dense `n` × `n` matrix, in row-major order, whose condition number is `cond`
+    /// up to rounding error.
+    ///
+    /// The matrix is constructed as `U * S * Vᵀ` from two random orthogonal matrices `U` and `V`
+    /// and a diagonal matrix `S` of singular values spaced geometrically from `1` down to
+    /// `1 / cond`. This provides test inputs of controlled difficulty for numerical solvers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cond` is less than one or not finite.
+    pub fn gen_matrix_conditioned(&mut self, n: usize, cond: f64) -> Vec<f64> {
+        assert!(
+            cond.is_finite() && cond >= 1.0,
+            "cond must be finite and at least one"
+        );
+        let u = self.gen_orthonormal_rows(n);
+        let v = self.gen_orthonormal_rows(n);
+        let sigma: Vec<f64> = (0..n)
+            .map(|k| cond.powf(-(k as f64) / (n - 1).max(1) as f64))
+            .collect();
+
+        let mut m = Vec::with_capacity(n * n);
+        for i in 0..n {
+            for j in 0..n {
+                m.push((0..n).map(|k| u[k * n + i] * sigma[k] * v[k * n + j]).sum());
+            }
+        }
+        m
+    }
+
+    /// Generates a uniformly random orthogonal `n` × `n` matrix by orthonormalizing the rows of
+    /// a Gaussian matrix with the modified Gram–Schmidt process.
+    fn gen_orthonormal_rows(&mut self, n: usize) -> Vec<f64> {
+        let mut q = Vec::with_capacity(n * n);
+        for _ in 0..n {
+            let mut row: Vec<f64> = (0..n).map(|_| self.next_standard_normal()).collect();
+            let norm = loop {
+                for prev in q.chunks_exact(n) {
+                    let dot: f64 = row.iter().zip(prev).map(|(x, y)| x * y).sum();
+                    row.iter_mut().zip(prev).for_each(|(x, y)| *x -= dot * y);
+                }
+                let norm = row.iter().map(|x| x * x).sum::<f64>().sqrt();
+                // redraw in the unlikely case that the row is nearly dependent on the others
+                if norm > 1e-6 {
+                    break norm;
+                }
+                row.iter_mut()
+                    .for_each(|x| *x = self.next_standard_normal());
+            };
+            q.extend(row.iter().map(|x| x / norm));
+        }
+        q
+    }
+
+    /// Fills `out` with a sample from the multivariate normal distribution with the specified
+    /// `mean` and covariance `L * Lᵀ`, where `chol` is the packed lower-triangular factor `L`.
+    ///
+    /// The sample is computed as `mean + L * z` from a vector `z` of independent standard normal
+    /// deviates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out.len()` differs from `mean.len()` or `chol.len()` is not
+    /// `mean.len() * (mean.len() + 1) / 2`.
+    pub fn next_multivariate_normal(&mut self, mean: &[f64], chol: &[f64], out: &mut [f64]) {
+        let dim = mean.len();
+        assert_eq!(out.len(), dim, "output length must match mean length");
+        assert_eq!(
+            chol.len(),
+            dim * (dim + 1) / 2,
+            "chol must be a packed lower-triangular matrix of the mean dimension"
+        );
+        for z in out.iter_mut() {
+            *z = self.next_standard_normal();
+        }
+        // fill from the bottom row so that `out[..=i]` still holds `z` while computing row `i`
+        for i in (0..dim).rev() {
+            let row = &chol[i * (i + 1) / 2..][..=i];
+            out[i] = mean[i] + row.iter().zip(&out[..=i]).map(|(l, z)| l * z).sum::<f64>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    use alloc::{vec, vec::Vec};
+
+    /// Computes `L * Lᵀ` as a dense row-major matrix from a packed lower-triangular `L`.
+    fn gram(chol: &[f64], dim: usize) -> Vec<f64> {
+        let at = |i: usize, j: usize| chol[i * (i + 1) / 2 + j];
+        let mut m = vec![0.0; dim * dim];
+        for i in 0..dim {
+            for j in 0..dim {
+                m[i * dim + j] = (0..=i.min(j)).map(|k| at(i, k) * at(j, k)).sum();
+            }
+        }
+        m
+    }
+
+    /// Tests that `gen_correlation_cholesky` yields a valid correlation matrix factor.
+    #[test]
+    fn gen_correlation_cholesky() {
+        let mut g = Pcg32::new(0x2431_85be_4ee4_b28c, 0x550c_7dc3_d5ff_b4e2);
+        for dim in [0, 1, 2, 5, 12] {
+            let chol = g.gen_correlation_cholesky(dim);
+            assert_eq!(chol.len(), dim * (dim + 1) / 2);
+            let m = gram(&chol, dim);
+            for i in 0..dim {
+                assert!(chol[i * (i + 1) / 2 + i] > 0.0);
+                assert!((m[i * dim + i] - 1.0).abs() < 1e-12);
+                for j in 0..dim {
+                    assert!(m[i * dim + j].abs() <= 1.0 + 1e-12);
+                    assert_eq!(m[i * dim + j], m[j * dim + i]);
+                }
+            }
+
+            // a random quadratic form of a positive semi-definite matrix is non-negative
+            for _ in 0..100 {
+                let x: Vec<f64> = (0..dim).map(|_| g.next_standard_normal()).collect();
+                let q: f64 = (0..dim * dim).map(|k| x[k / dim] * m[k] * x[k % dim]).sum();
+                assert!(q >= -1e-12);
+            }
+        }
+    }
+
+    /// Computes the eigenvalues of a dense symmetric matrix by the cyclic Jacobi method.
+    fn symmetric_eigenvalues(mut a: Vec<f64>, n: usize) -> Vec<f64> {
+        for _ in 0..100 {
+            let off: f64 = (0..n * n)
+                .filter(|k| k / n != k % n)
+                .map(|k| a[k] * a[k])
+                .sum();
+            if off < 1e-30 {
+                break;
+            }
+            for p in 0..n {
+                for q in p + 1..n {
+                    if a[p * n + q] == 0.0 {
+                        continue;
+                    }
+                    let theta = (a[q * n + q] - a[p * n + p]) / (2.0 * a[p * n + q]);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let s = t * c;
+                    for k in 0..n {
+                        let (akp, akq) = (a[k * n + p], a[k * n + q]);
+                        a[k * n + p] = c * akp - s * akq;
+                        a[k * n + q] = s * akp + c * akq;
+                    }
+                    for k in 0..n {
+                        let (apk, aqk) = (a[p * n + k], a[q * n + k]);
+                        a[p * n + k] = c * apk - s * aqk;
+                        a[q * n + k] = s * apk + c * aqk;
+                    }
+                }
+            }
+        }
+        (0..n).map(|i| a[i * n + i]).collect()
+    }
+
+    /// Tests that `gen_matrix_conditioned` attains the target condition number.
+    #[test]
+    fn gen_matrix_conditioned() {
+        let mut g = Pcg32::new(0x9c10_0d4c_cb3e_42b6, 0x4cc5_d4be_cb3e_42b6);
+        for (n, cond) in [(1, 1.0), (2, 10.0), (5, 1.0), (8, 1e3), (12, 1e6)] {
+            let m = g.gen_matrix_conditioned(n, cond);
+            assert_eq!(m.len(), n * n);
+
+            // the eigenvalues of `Mᵀ * M` are the squared singular values of `M`
+            let mut mtm = vec![0.0; n * n];
+            for i in 0..n {
+                for j in 0..n {
+                    mtm[i * n + j] = (0..n).map(|k| m[k * n + i] * m[k * n + j]).sum();
+                }
+            }
+            let eig = symmetric_eigenvalues(mtm, n);
+            let max = eig.iter().fold(0.0f64, |acc, &x| acc.max(x));
+            let min = eig.iter().fold(f64::INFINITY, |acc, &x| acc.min(x));
+            // squaring loses about half of the significant digits of the smallest singular value
+            assert!(((max / min).sqrt() / cond - 1.0).abs() < 1e-3);
+            assert!((max - 1.0).abs() < 1e-9);
+        }
+
+        let (a, b) = (
+            g.gen_matrix_conditioned(4, 10.0),
+            g.gen_matrix_conditioned(4, 10.0),
+        );
+        assert_ne!(a, b);
+        assert!(g.gen_matrix_conditioned(0, 5.0).is_empty());
+    }
+
+    /// Tests that the empirical covariance of `next_multivariate_normal` approaches `L * Lᵀ`.
+    #[test]
+    fn next_multivariate_normal() {
+        let mut g = Pcg32::new(0x8010_2e82_7c5f_3d9a, 0x0f6c_5b4e_9fe1_7c32);
+        let mean = [1.0, -2.0, 0.5];
+        let chol = [1.0, 0.5, 0.8, -0.3, 0.2, 0.9];
+        let cov = gram(&chol, 3);
+
+        let n = 200_000;
+        let mut sum = [0.0; 3];
+        let mut sum_sq = [0.0; 9];
+        let mut out = [0.0; 3];
+        for _ in 0..n {
+            g.next_multivariate_normal(&mean, &chol, &mut out);
+            for i in 0..3 {
+                sum[i] += out[i];
+                for j in 0..3 {
+                    sum_sq[i * 3 + j] += (out[i] - mean[i]) * (out[j] - mean[j]);
+                }
+            }
+        }
+        for i in 0..3 {
+            assert!((sum[i] / n as f64 - mean[i]).abs() < 0.01);
+        }
+        for k in 0..9 {
+            assert!((sum_sq[k] / n as f64 - cov[k]).abs() < 0.01);
+        }
+
+        g.next_multivariate_normal(&[], &[], &mut []);
+    }
+
+    /// Tests that `next_multivariate_normal` rejects a factor of the wrong dimension.
+    #[test]
+    #[should_panic]
+    fn next_multivariate_normal_dimension_mismatch() {
+        Pcg32::default().next_multivariate_normal(&[0.0; 3], &[1.0; 3], &mut [0.0; 3]);
+    }
+}
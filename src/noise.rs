@@ -0,0 +1,143 @@
+//! Noise with shaped power spectra.
+
+use crate::Pcg32;
+
+use alloc::{vec, vec::Vec};
+
+impl Pcg32 {
+    /// Generates a time series of `n` samples whose power spectrum follows `1 / f^beta`.
+    ///
+    /// White Gaussian noise is shaped in the frequency domain and transformed back by an inverse
+    /// FFT over the next power of two of `n`, of which the first `n` samples are returned. The
+    /// series over the whole transform length has zero mean and unit variance. `beta = 0` yields
+    /// white noise, `beta = 1` pink noise, and `beta = 2` brown noise, while larger values
+    /// concentrate the power in lower frequencies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `beta` is not finite.
+    pub fn gen_colored_noise(&mut self, n: usize, beta: f64) -> Vec<f64> {
+        assert!(beta.is_finite(), "beta must be finite");
+        if n == 0 {
+            return Vec::new();
+        }
+        let m = n.next_power_of_two().max(2);
+        let (mut re, mut im) = (vec![0.0; m], vec![0.0; m]);
+        for k in 1..=m / 2 {
+            let amplitude = (k as f64).powf(-beta / 2.0);
+            re[k] = amplitude * self.next_standard_normal();
+            if k < m / 2 {
+                im[k] = amplitude * self.next_standard_normal();
+                // mirror the spectrum so that the series is real
+                re[m - k] = re[k];
+                im[m - k] = -im[k];
+            }
+        }
+
+        inverse_fft(&mut re, &mut im);
+        let rms = (re.iter().map(|x| x * x).sum::<f64>() / m as f64).sqrt();
+        re.truncate(n);
+        re.iter_mut().for_each(|x| *x /= rms);
+        re
+    }
+}
+
+/// Computes the unnormalized inverse discrete Fourier transform in place by the iterative
+/// radix-2 Cooley–Tukey algorithm. The length must be a power of two.
+fn inverse_fft(re: &mut [f64], im: &mut [f64]) {
+    use core::f64::consts::TAU;
+
+    let n = re.len();
+    debug_assert!(n.is_power_of_two() && im.len() == n);
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        for start in (0..n).step_by(len) {
+            for k in 0..half {
+                let (s, c) = (TAU * k as f64 / len as f64).sin_cos();
+                let (a, b) = (start + k, start + k + half);
+                let (tr, ti) = (re[b] * c - im[b] * s, re[b] * s + im[b] * c);
+                (re[b], im[b]) = (re[a] - tr, im[a] - ti);
+                (re[a], im[a]) = (re[a] + tr, im[a] + ti);
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    use alloc::vec::Vec;
+
+    /// Computes the lag-1 autocorrelation of a series.
+    fn lag1_autocorrelation(x: &[f64]) -> f64 {
+        let mean = x.iter().sum::<f64>() / x.len() as f64;
+        let var: f64 = x.iter().map(|v| (v - mean) * (v - mean)).sum();
+        let cov: f64 = x.windows(2).map(|w| (w[0] - mean) * (w[1] - mean)).sum();
+        cov / var
+    }
+
+    /// Tests that `gen_colored_noise` shifts power to lower frequencies as `beta` grows.
+    #[test]
+    fn gen_colored_noise() {
+        let mut g = Pcg32::new(0x1f9d_2b4c_8a7e_6305, 0x7c3a_e1b9_5d02_f468);
+
+        let white = g.gen_colored_noise(1 << 14, 0.0);
+        assert_eq!(white.len(), 1 << 14);
+        let mean = white.iter().sum::<f64>() / white.len() as f64;
+        let var = white.iter().map(|x| x * x).sum::<f64>() / white.len() as f64;
+        assert!(mean.abs() < 1e-9);
+        assert!((var - 1.0).abs() < 1e-9);
+        assert!(lag1_autocorrelation(&white).abs() < 0.03);
+
+        let mut prev = lag1_autocorrelation(&white);
+        for beta in [0.5, 1.0, 2.0] {
+            let r = lag1_autocorrelation(&g.gen_colored_noise(10_000, beta));
+            assert!(r > prev + 0.1);
+            prev = r;
+        }
+        assert!(prev > 0.95);
+
+        assert!(g.gen_colored_noise(0, 1.0).is_empty());
+        assert_eq!(g.gen_colored_noise(1, 1.0).len(), 1);
+    }
+
+    /// Tests that `inverse_fft` agrees with the naive inverse discrete Fourier transform.
+    #[test]
+    fn inverse_fft() {
+        use core::f64::consts::TAU;
+
+        let mut g = Pcg32::new(0x0123_4567_89ab_cdef, 0x0f1e_2d3c_4b5a_6978);
+        for n in [1, 2, 4, 16] {
+            let re: Vec<f64> = (0..n).map(|_| g.next_standard_normal()).collect();
+            let im: Vec<f64> = (0..n).map(|_| g.next_standard_normal()).collect();
+            let (mut fre, mut fim) = (re.clone(), im.clone());
+            super::inverse_fft(&mut fre, &mut fim);
+            for t in 0..n {
+                let (mut sr, mut si) = (0.0, 0.0);
+                for k in 0..n {
+                    let (s, c) = (TAU * (k * t) as f64 / n as f64).sin_cos();
+                    sr += re[k] * c - im[k] * s;
+                    si += re[k] * s + im[k] * c;
+                }
+                assert!((fre[t] - sr).abs() < 1e-9 && (fim[t] - si).abs() < 1e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,451 @@
+//! Sampling designs for Monte Carlo methods and experiments.
+
+use crate::Pcg32;
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+impl Pcg32 {
+    /// Fills `out` with `n` positive weights that sum to one, up to rounding error.
+    ///
+    /// The weights are obtained by normalizing `n` uniform draws from `(0, 1]`, so they form a
+    /// random composition of unity. Note that this is not uniformly distributed over the
+    /// probability simplex.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out.len()` is not `n`.
+    #[cfg(feature = "alloc")]
+    pub fn gen_weights(&mut self, n: usize, out: &mut [f64]) {
+        assert_eq!(out.len(), n, "output buffer length must be n");
+        let mut total = 0.0;
+        for e in out.iter_mut() {
+            *e = 1.0 - self.next_f64();
+            total += *e;
+        }
+        for e in out {
+            *e /= total;
+        }
+    }
+
+    /// Resamples particles in proportion to `weights` by systematic resampling, returning the
+    /// index of the particle selected for each of `weights.len()` slots.
+    ///
+    /// A single uniform offset determines `n` evenly spaced pointers into the cumulative
+    /// weights, so each particle is selected either `floor(n * w)` or `ceil(n * w)` times, where
+    /// `w` is its normalized weight. This keeps the resampling noise of particle filters lower
+    /// than that of independent draws. The returned indices are in non-decreasing order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any weight is negative or not finite, or if `weights` is nonempty and sums to
+    /// zero.
+    #[cfg(feature = "alloc")]
+    pub fn systematic_resample(&mut self, weights: &[f64]) -> Vec<usize> {
+        let n = weights.len();
+        if n == 0 {
+            return Vec::new();
+        }
+        assert!(
+            weights.iter().all(|w| w.is_finite() && *w >= 0.0),
+            "weights must be finite and non-negative"
+        );
+        let total: f64 = weights.iter().sum();
+        assert!(total > 0.0, "weights must not sum to zero");
+
+        // rounding errors may push the last pointers past the cumulative sum, which must not
+        // select the trailing particles of zero weight
+        let last = weights.iter().rposition(|&w| w > 0.0).unwrap();
+        let step = total / n as f64;
+        let mut pointer = self.next_f64() * step;
+        let (mut j, mut cumulative) = (0, weights[0]);
+        let mut indices = Vec::with_capacity(n);
+        for _ in 0..n {
+            while pointer >= cumulative && j < last {
+                j += 1;
+                cumulative += weights[j];
+            }
+            indices.push(j);
+            pointer += step;
+        }
+        indices
+    }
+
+    /// Fills `out` with one uniform from each of `strata` equal-width strata of `[0, 1)`, so that
+    /// `out[i]` falls in `[i / strata, (i + 1) / strata)`.
+    ///
+    /// Such stratified samples cover the unit interval more evenly than independent uniforms,
+    /// which reduces the variance of Monte Carlo estimates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out.len()` is not `strata`.
+    #[cfg(feature = "alloc")]
+    pub fn stratified_uniforms(&mut self, strata: usize, out: &mut [f64]) {
+        assert_eq!(out.len(), strata, "output buffer length must be strata");
+        let n = strata as f64;
+        for (i, e) in out.iter_mut().enumerate() {
+            *e = self.uniform_f64(i as f64 / n, (i + 1) as f64 / n);
+        }
+    }
+
+    /// Generates one point `(x, y)` uniformly placed within each cell of a `rows` × `cols` grid
+    /// over the unit square, in row-major order.
+    ///
+    /// The point at index `r * cols + c` lies in `[c / cols, (c + 1) / cols)` ×
+    /// `[r / rows, (r + 1) / rows)`. Such jittered samples cover the square more evenly than
+    /// independent uniform points while avoiding the aliasing of a regular grid.
+    #[cfg(feature = "alloc")]
+    pub fn jittered_grid(&mut self, rows: usize, cols: usize) -> Vec<(f64, f64)> {
+        let (nr, nc) = (rows as f64, cols as f64);
+        let mut points = Vec::with_capacity(rows * cols);
+        for r in 0..rows {
+            for c in 0..cols {
+                let x = self.uniform_f64(c as f64 / nc, (c + 1) as f64 / nc);
+                let y = self.uniform_f64(r as f64 / nr, (r + 1) as f64 / nr);
+                points.push((x, y));
+            }
+        }
+        points
+    }
+
+    /// Generates a Latin hypercube design of `samples` points in the `dims`-dimensional unit
+    /// hypercube, returned as `samples` rows of `dims` coordinates each.
+    ///
+    /// Each dimension is divided into `samples` equal-width strata of `[0, 1)`, and the strata
+    /// are assigned to the points by an independent random permutation per dimension, so that
+    /// every stratum of every dimension contains exactly one point.
+    #[cfg(feature = "alloc")]
+    pub fn latin_hypercube(&mut self, samples: usize, dims: usize) -> Vec<Vec<f64>> {
+        let n = samples as f64;
+        let mut points = vec![vec![0.0; dims]; samples];
+        let mut strata: Vec<usize> = (0..samples).collect();
+        for d in 0..dims {
+            self.shuffle(&mut strata);
+            for (point, &k) in points.iter_mut().zip(&strata) {
+                point[d] = self.uniform_f64(k as f64 / n, (k + 1) as f64 / n);
+            }
+        }
+        points
+    }
+
+    /// Generates `N` uniforms in `[0, 1)` in non-decreasing order.
+    ///
+    /// This uses the exponential spacings method, normalizing the cumulative sums of `N + 1`
+    /// standard exponential deviates, so the order statistics are produced in `O(N)` time
+    /// without sorting.
+    #[cfg(feature = "std")]
+    pub fn gen_sorted_uniforms<const N: usize>(&mut self) -> [f64; N] {
+        let mut out = [0.0; N];
+        self.fill_sorted_uniforms(&mut out);
+        out
+    }
+
+    /// Generates `count` timestamps uniformly distributed in `[start, end)` in non-decreasing
+    /// order.
+    ///
+    /// This maps sorted uniforms from the exponential spacings method onto the window, so the
+    /// result is produced in order without sorting, which is handy to fabricate event logs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start >= end` while `count` is nonzero.
+    #[cfg(feature = "alloc")]
+    pub fn gen_sorted_timestamps(&mut self, start: u64, end: u64, count: usize) -> Vec<u64> {
+        if count == 0 {
+            return Vec::new();
+        }
+        assert!(start < end, "window must not be empty");
+        let width = end - start;
+        let mut uniforms = vec![0.0; count];
+        self.fill_sorted_uniforms(&mut uniforms);
+        uniforms
+            .into_iter()
+            .map(|u| start + ((u * width as f64) as u64).min(width - 1))
+            .collect()
+    }
+
+    /// Generates a uniform `u` in `(0, 1)` together with its antithetic partner `1 - u`.
+    ///
+    /// Averaging an integrand over such negatively correlated pairs is a classic variance
+    /// reduction technique for Monte Carlo integration. Zero is excluded from `u` so that both
+    /// values lie in `[0, 1)` and sum to exactly one.
+    #[cfg(feature = "std")]
+    pub fn next_f64_antithetic(&mut self) -> (f64, f64) {
+        let u = self.next_f64_nonzero();
+        (u, 1.0 - u)
+    }
+
+    /// Returns the index of the largest logit after perturbing each with independent Gumbel
+    /// noise, or `None` if `logits` is empty.
+    ///
+    /// By the Gumbel-max trick, index `i` is returned with probability
+    /// `exp(logits[i]) / sum(exp(logits))`, i.e., this samples from the softmax distribution
+    /// without normalizing or exponentiating the logits. A logit of negative infinity is never
+    /// chosen unless all logits are.
+    #[cfg(feature = "std")]
+    pub fn gumbel_argmax(&mut self, logits: &[f64]) -> Option<usize> {
+        let mut argmax = None;
+        let mut max = f64::NEG_INFINITY;
+        for (i, &logit) in logits.iter().enumerate() {
+            let key = logit - (-self.next_f64_nonzero().ln()).ln();
+            if argmax.is_none() || key > max {
+                argmax = Some(i);
+                max = key;
+            }
+        }
+        argmax
+    }
+
+    /// Fills a slice with uniforms in `[0, 1)` in non-decreasing order using exponential
+    /// spacings.
+    ///
+    /// Every spacing is strictly positive, and a quotient that rounds up to one is clamped to
+    /// the largest `f64` below one.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn fill_sorted_uniforms(&mut self, out: &mut [f64]) {
+        let mut sum = 0.0;
+        for e in out.iter_mut() {
+            sum += self.next_exponential();
+            *e = sum;
+        }
+        sum += self.next_exponential();
+        for e in out {
+            *e = (*e / sum).min(1.0 - f64::EPSILON / 2.0);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::Pcg32;
+
+    /// Tests that `gen_weights` produces non-negative weights summing to one.
+    #[test]
+    fn gen_weights() {
+        let mut g = Pcg32::new(0x1f83_d9ab_fb41_bd6b, 0x5be0_cd19_137e_2179);
+        let mut buf = [0.0; 100];
+        for n in [0, 1, 2, 5, 17, 100] {
+            for _ in 0..1_000 {
+                let out = &mut buf[..n];
+                g.gen_weights(n, out);
+                assert!(out.iter().all(|&w| w >= 0.0));
+                if n > 0 {
+                    assert!((out.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+                }
+            }
+        }
+    }
+
+    /// Tests that each value of `stratified_uniforms` falls within its stratum.
+    #[test]
+    fn stratified_uniforms() {
+        let mut g = Pcg32::new(0xa450_6ceb_bef9_a3f7, 0xc671_78f2_e372_532b);
+        let mut buf = [0.0; 1000];
+        for strata in [0, 1, 2, 3, 10, 1000] {
+            let mut sum = 0.0;
+            for _ in 0..100 {
+                let out = &mut buf[..strata];
+                g.stratified_uniforms(strata, out);
+                for (i, &x) in out.iter().enumerate() {
+                    let n = strata as f64;
+                    assert!(i as f64 / n <= x && x < (i + 1) as f64 / n);
+                    sum += x * n - i as f64;
+                }
+            }
+            if strata > 0 {
+                assert!((sum / (100 * strata) as f64 - 0.5).abs() < 0.1);
+            }
+        }
+    }
+
+    /// Tests that `jittered_grid` places one point within each cell.
+    #[test]
+    fn jittered_grid() {
+        let mut g = Pcg32::new(0x28db_77f5_2304_7d84, 0x32ca_ab7b_40c7_2493);
+        for (rows, cols) in [(0, 5), (5, 0), (1, 1), (3, 7), (100, 100)] {
+            let points = g.jittered_grid(rows, cols);
+            assert_eq!(points.len(), rows * cols);
+            let mut sum = 0.0;
+            for (i, &(x, y)) in points.iter().enumerate() {
+                let (r, c) = ((i / cols) as f64, (i % cols) as f64);
+                let (nr, nc) = (rows as f64, cols as f64);
+                assert!(c / nc <= x && x < (c + 1.0) / nc);
+                assert!(r / nr <= y && y < (r + 1.0) / nr);
+                sum += x * nc - c + y * nr - r;
+            }
+            if rows * cols >= 1000 {
+                assert!((sum / (2 * rows * cols) as f64 - 0.5).abs() < 0.02);
+            }
+        }
+    }
+
+    /// Tests that `systematic_resample` selects each particle in proportion to its weight.
+    #[test]
+    fn systematic_resample() {
+        let mut g = Pcg32::new(0x428a_2f98_d728_ae22, 0x7137_4491_23ef_65cd);
+        let weights = [0.5, 0.0, 3.0, 1.0, 0.25, 2.0, 0.0, 1.25];
+        let total: f64 = weights.iter().sum();
+        for _ in 0..1000 {
+            let indices = g.systematic_resample(&weights);
+            assert_eq!(indices.len(), weights.len());
+            assert!(indices.windows(2).all(|w| w[0] <= w[1]));
+            for (i, &w) in weights.iter().enumerate() {
+                let count = indices.iter().filter(|&&j| j == i).count() as f64;
+                let expected = w / total * weights.len() as f64;
+                assert!(count == expected.floor() || count == expected.ceil());
+            }
+        }
+
+        assert!(g.systematic_resample(&[]).is_empty());
+        assert_eq!(g.systematic_resample(&[0.0, 0.0, 7.0]), [2, 2, 2]);
+    }
+
+    /// Tests that `systematic_resample` never selects trailing particles of zero weight.
+    #[test]
+    fn systematic_resample_trailing_zeros() {
+        // this state yields the largest uniform offset, pushing the last pointer as far as it goes
+        let g = Pcg32 {
+            state: 0x07ff_fe00_0780_01e0,
+            inc: 0xdd96_21d6_9db1_8d81,
+        };
+        assert_eq!(g.clone().next_f64(), 1.0 - f64::EPSILON / 2.0);
+        for k in 1..=64 {
+            for trailing in [1, 5] {
+                let mut weights = [0.0; 69];
+                for (i, w) in weights[..k].iter_mut().enumerate() {
+                    *w = 0.1 + 0.01 * i as f64;
+                }
+                let weights = &weights[..k + trailing];
+                let indices = g.clone().systematic_resample(weights);
+                assert!(indices.iter().all(|&j| weights[j] > 0.0));
+            }
+        }
+    }
+
+    /// Tests that `systematic_resample` rejects weights summing to zero.
+    #[test]
+    #[should_panic]
+    fn systematic_resample_zero_total() {
+        Pcg32::default().systematic_resample(&[0.0, 0.0]);
+    }
+
+    /// Tests that `stratified_uniforms` rejects a buffer of the wrong length.
+    #[test]
+    #[should_panic]
+    fn stratified_uniforms_length_mismatch() {
+        Pcg32::default().stratified_uniforms(3, &mut [0.0; 4]);
+    }
+
+    /// Tests that every column of `latin_hypercube` has exactly one sample per stratum.
+    #[test]
+    fn latin_hypercube() {
+        let mut g = Pcg32::new(0xca62_c1d6_8f1b_bcdc, 0x6ed9_eba1_5a82_7999);
+        for (samples, dims) in [(0, 3), (3, 0), (1, 1), (10, 4), (100, 7)] {
+            let points = g.latin_hypercube(samples, dims);
+            assert_eq!(points.len(), samples);
+            let n = samples as f64;
+            for d in 0..dims {
+                for k in 0..samples {
+                    let (lo, hi) = (k as f64 / n, (k + 1) as f64 / n);
+                    let hits = points.iter().filter(|p| lo <= p[d] && p[d] < hi).count();
+                    assert_eq!(hits, 1);
+                }
+            }
+            assert!(points.iter().all(|p| p.len() == dims));
+        }
+
+        // the strata are permuted independently across dimensions
+        let points = g.latin_hypercube(50, 2);
+        assert!(points
+            .iter()
+            .any(|p| (p[0] * 50.0) as u32 != (p[1] * 50.0) as u32));
+    }
+
+    /// Tests that `gen_sorted_uniforms` returns sorted values with uniform marginals.
+    #[cfg(feature = "std")]
+    #[test]
+    fn gen_sorted_uniforms() {
+        let mut g = Pcg32::new(0x7137_4491_b5c0_fbcf, 0xe9b5_dba5_3956_c25b);
+        let n = 10_000;
+        let mut sum = [0.0; 4];
+        for _ in 0..n {
+            let u: [f64; 4] = g.gen_sorted_uniforms();
+            assert!(u.windows(2).all(|w| w[0] <= w[1]));
+            assert!(u.iter().all(|&x| (0.0..1.0).contains(&x)));
+            for (s, x) in sum.iter_mut().zip(u) {
+                *s += x;
+            }
+        }
+        // the k-th of N uniform order statistics has mean k / (N + 1)
+        for (k, s) in sum.iter().enumerate() {
+            assert!((s / n as f64 - (k + 1) as f64 / 5.0).abs() < 0.01);
+        }
+
+        let empty: [f64; 0] = g.gen_sorted_uniforms();
+        assert_eq!(empty, [0.0; 0]);
+    }
+
+    /// Tests that `gen_sorted_timestamps` returns sorted timestamps within the window.
+    #[test]
+    fn gen_sorted_timestamps() {
+        let mut g = Pcg32::new(0x0f4e_d2c6_1d6e_3b5a, 0x8e9b_3d92_71f6_7c05);
+        for (start, end) in [(1_700_000_000, 1_700_086_400), (5, 6), (0, u64::MAX)] {
+            let count = 10_000;
+            let stamps = g.gen_sorted_timestamps(start, end, count);
+            assert_eq!(stamps.len(), count);
+            assert!(stamps.windows(2).all(|w| w[0] <= w[1]));
+            assert!(stamps.iter().all(|t| (start..end).contains(t)));
+
+            let mid = start + (end - start) / 2;
+            let below_mid = stamps.iter().filter(|&&t| t < mid).count() as f64 / count as f64;
+            if end - start > 1 {
+                assert!((below_mid - 0.5).abs() < 0.03);
+            }
+        }
+
+        assert!(g.gen_sorted_timestamps(3, 3, 0).is_empty());
+    }
+
+    /// Tests that `next_f64_antithetic` returns pairs in `[0, 1)` summing to one.
+    #[cfg(feature = "std")]
+    #[test]
+    fn next_f64_antithetic() {
+        let mut g = Pcg32::new(0x8cc7_0208_1a64_39ec, 0x90be_fffa_2363_1e28);
+        let n = 100_000;
+        let mut sum = 0.0;
+        for _ in 0..n {
+            let (u, v) = g.next_f64_antithetic();
+            assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v));
+            assert_eq!(u + v, 1.0);
+            sum += u;
+        }
+        assert!((sum / n as f64 - 0.5).abs() < 0.01);
+
+        // zero is rejected so that the partner never reaches one
+        let mut g = Pcg32 { state: 0, inc: 1 };
+        let (u, v) = g.next_f64_antithetic();
+        assert!(u > 0.0 && v < 1.0);
+    }
+
+    /// Tests that `gumbel_argmax` samples indices from the softmax of the logits.
+    #[cfg(feature = "std")]
+    #[test]
+    fn gumbel_argmax() {
+        let mut g = Pcg32::new(0xa54f_f53a_5f1d_36f1, 0x510e_527f_ade6_82d1);
+        let logits = [1.0, -0.5, 2.0, 0.0, f64::NEG_INFINITY];
+        let total: f64 = logits.iter().map(|l| l.exp()).sum();
+        let n = 100_000;
+        let mut freq = [0u32; 5];
+        for _ in 0..n {
+            freq[g.gumbel_argmax(&logits).unwrap()] += 1;
+        }
+        for (f, l) in freq.into_iter().zip(logits) {
+            assert!((f as f64 / n as f64 - l.exp() / total).abs() < 0.01);
+        }
+        assert_eq!(freq[4], 0);
+
+        assert_eq!(g.gumbel_argmax(&[]), None);
+        assert_eq!(g.gumbel_argmax(&[-3.0]), Some(0));
+    }
+}
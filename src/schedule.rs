@@ -0,0 +1,125 @@
+//! Randomized timing for scheduling and event fabrication.
+
+use crate::Pcg32;
+
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+impl Pcg32 {
+    /// Perturbs `base` by a uniformly distributed offset of up to `±pct` of itself.
+    ///
+    /// The result is an integer uniformly distributed in `[base - d, base + d]`, where
+    /// `d = floor(base * pct)`, saturating at `u64::MAX`. This is useful to spread the load of
+    /// scheduled tasks that would otherwise fire simultaneously.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pct` is not in `[0, 1]`.
+    pub fn jitter(&mut self, base: u64, pct: f64) -> u64 {
+        assert!((0.0..=1.0).contains(&pct), "pct must be in [0, 1]");
+        let delta = ((base as f64 * pct) as u64).min(base);
+        let (low, high) = (base - delta, base.saturating_add(delta));
+        match (high - low).checked_add(1) {
+            Some(span) => low + self.bounded_u64(span),
+            None => self.next_u64(),
+        }
+    }
+
+    /// Generates a randomized exponential backoff schedule of `steps` delays in milliseconds.
+    ///
+    /// The `i`-th delay is the nominal value `base_ms * factor^i`, saturating at `u64::MAX` and
+    /// truncated to an integer, perturbed by up to `±jitter` of itself through
+    /// [`jitter`](Self::jitter). Randomizing the delays keeps clients that failed at the same
+    /// time from retrying in lockstep.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is negative or not finite, or if `jitter` is not in `[0, 1]`.
+    #[cfg(feature = "std")]
+    pub fn backoff_schedule(
+        &mut self,
+        base_ms: u64,
+        factor: f64,
+        steps: usize,
+        jitter: f64,
+    ) -> Vec<u64> {
+        assert!(
+            factor.is_finite() && factor >= 0.0,
+            "factor must be finite and non-negative"
+        );
+        assert!((0.0..=1.0).contains(&jitter), "jitter must be in [0, 1]");
+        let mut nominal = base_ms as f64;
+        (0..steps)
+            .map(|_| {
+                let delay = self.jitter(nominal as u64, jitter);
+                nominal *= factor;
+                delay
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    /// Tests that `jitter` stays within the budget and averages near the base.
+    #[test]
+    fn jitter() {
+        let mut g = Pcg32::new(0x550c_7dc3_d5ff_b4e2, 0x72be_5d74_f27b_896f);
+        for (base, pct) in [(0, 0.5), (1, 1.0), (1000, 0.0), (1000, 0.1), (60_000, 0.25)] {
+            let n = 100_000;
+            let mut sum = 0;
+            for _ in 0..n {
+                let x = g.jitter(base, pct);
+                let d = (base as f64 * pct) as u64;
+                assert!(base - d <= x && x <= base + d);
+                sum += x;
+            }
+            let tolerance = (0.01 * base as f64 * pct).max(0.01);
+            assert!((sum as f64 / n as f64 - base as f64).abs() <= tolerance);
+        }
+
+        for _ in 0..1000 {
+            assert!(g.jitter(u64::MAX, 0.5) >= u64::MAX / 2);
+            g.jitter(u64::MAX, 1.0);
+        }
+    }
+
+    /// Tests that `backoff_schedule` grows geometrically within the jitter bounds.
+    #[cfg(feature = "std")]
+    #[test]
+    fn backoff_schedule() {
+        let mut g = Pcg32::new(0x8c3f_2e19_d0a7_5b64, 0x17e4_c9a2_6b0d_f385);
+        let (base, factor, steps, pct) = (100, 2.0, 10, 0.2);
+        let n = 10_000;
+        let mut sum = [0u64; 10];
+        for _ in 0..n {
+            let schedule = g.backoff_schedule(base, factor, steps, pct);
+            assert_eq!(schedule.len(), steps);
+            for (i, (s, &x)) in sum.iter_mut().zip(&schedule).enumerate() {
+                let nominal = base << i;
+                let d = (nominal as f64 * pct) as u64;
+                assert!(nominal - d <= x && x <= nominal + d);
+                *s += x;
+            }
+        }
+        for (i, s) in sum.iter().enumerate() {
+            let mean = *s as f64 / n as f64;
+            assert!((mean / (base << i) as f64 - 1.0).abs() < 0.01);
+        }
+
+        assert!(g.backoff_schedule(100, 2.0, 0, 0.5).is_empty());
+        assert_eq!(g.backoff_schedule(100, 1.5, 3, 0.0), [100, 150, 225]);
+        let huge = g.backoff_schedule(1 << 40, 1e10, 5, 0.0);
+        assert_eq!(huge[3..], [u64::MAX, u64::MAX]);
+    }
+
+    /// Tests that `backoff_schedule` rejects a negative factor.
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic]
+    fn backoff_schedule_negative_factor() {
+        Pcg32::default().backoff_schedule(100, -2.0, 3, 0.1);
+    }
+}
@@ -0,0 +1,188 @@
+//! Constructors that derive seeds from other values.
+
+use crate::Pcg32;
+
+/// Weyl sequence increment of SplitMix64.
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Scrambles a 64-bit value with the SplitMix64 output function.
+pub(crate) const fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Hashes a string with 64-bit FNV-1a followed by the SplitMix64 output function.
+const fn hash_str(s: &str) -> u64 {
+    let bytes = s.as_bytes();
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut i = 0;
+    while i < bytes.len() {
+        h ^= bytes[i] as u64;
+        h = h.wrapping_mul(0x0100_0000_01b3);
+        i += 1;
+    }
+    mix64(h)
+}
+
+impl Pcg32 {
+    /// Initializes a generator from a single `u64` seed, expanding it into `initstate` and
+    /// `initseq` with SplitMix64.
+    pub(crate) const fn seed_from_u64(seed: u64) -> Self {
+        let s = seed.wrapping_add(GOLDEN_GAMMA);
+        Self::new(mix64(s), mix64(s.wrapping_add(GOLDEN_GAMMA)))
+    }
+
+    /// Initializes a generator from a human-friendly string seed such as a world name.
+    ///
+    /// The UTF-8 bytes of `seed` are hashed with 64-bit FNV-1a, the hash is scrambled with the
+    /// SplitMix64 output function, and the result is expanded into `initstate` and `initseq`
+    /// with two further SplitMix64 steps. This mapping is part of the stable interface and will
+    /// not change, so equal strings yield equal sequences across versions and platforms, while
+    /// similar strings such as `"a"` and `"b"` yield unrelated generators.
+    pub const fn seed_from_str(seed: &str) -> Self {
+        Self::seed_from_u64(hash_str(seed))
+    }
+
+    /// Initializes a generator by hashing two strings into `initstate` and `initseq`,
+    /// respectively.
+    ///
+    /// This is handy to seed from namespaced identifiers such as `(world_name, biome)`. The
+    /// order of the arguments matters, so swapping them generally yields a different generator.
+    pub const fn from_str_pair(a: &str, b: &str) -> Self {
+        Self::new(hash_str(a), hash_str(b))
+    }
+
+    /// Generates an `N`-byte seed for another random number generator.
+    ///
+    /// The bytes are the same as those written by [`fill_bytes`](Self::fill_bytes), so a master
+    /// generator can deterministically bootstrap generators of other libraries, e.g., through
+    /// `SeedableRng::from_seed`.
+    pub fn gen_seed<const N: usize>(&mut self) -> [u8; N] {
+        let mut seed = [0; N];
+        self.fill_bytes(&mut seed);
+        seed
+    }
+
+    /// Initializes a generator from the nanoseconds of the current system time.
+    ///
+    /// This is meant for casual programs that just need a different sequence on each run. The
+    /// system clock is a far weaker and more predictable source of entropy than the random
+    /// number generator of the operating system, so do not use this where unpredictability
+    /// matters.
+    #[cfg(feature = "std")]
+    pub fn from_time() -> Self {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        Self::seed_from_u64(nanos as u64 ^ (nanos >> 64) as u64)
+    }
+
+    /// Initializes a generator from 16 bytes of the random number generator of the operating
+    /// system, read as two little-endian `u64` seeds.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the message "failed to obtain entropy from the operating system" if the
+    /// operating system fails to provide random bytes.
+    #[cfg(feature = "getrandom")]
+    pub fn from_entropy() -> Self {
+        let mut seed = [0u8; 16];
+        getrandom::fill(&mut seed).expect("failed to obtain entropy from the operating system");
+        let (initstate, initseq) = seed.split_at(8);
+        Self::new(
+            u64::from_le_bytes(initstate.try_into().unwrap()),
+            u64::from_le_bytes(initseq.try_into().unwrap()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    /// Tests that `seed_from_str` is pinned to fixed outputs and separates similar strings.
+    #[test]
+    fn seed_from_str() {
+        let cases = [
+            ("", 0xa3ec_b1d9),
+            ("a", 0xeae8_1439),
+            ("b", 0x1dc3_d527),
+            ("world-1", 0x4b27_e333),
+        ];
+        for (seed, first) in cases {
+            assert_eq!(Pcg32::seed_from_str(seed).generate(), first);
+        }
+        for (i, (a, _)) in cases.iter().enumerate() {
+            for (b, _) in &cases[..i] {
+                assert_ne!(Pcg32::seed_from_str(a), Pcg32::seed_from_str(b));
+            }
+        }
+    }
+
+    /// Tests that `from_str_pair` is a deterministic and order-sensitive function of the pair.
+    #[test]
+    fn from_str_pair() {
+        let pairs = [
+            ("overworld", "forest"),
+            ("overworld", "desert"),
+            ("", ""),
+            ("a", "b"),
+        ];
+        for (a, b) in pairs {
+            assert_eq!(Pcg32::from_str_pair(a, b), Pcg32::from_str_pair(a, b));
+            if a != b {
+                assert_ne!(Pcg32::from_str_pair(a, b), Pcg32::from_str_pair(b, a));
+            }
+        }
+        for (i, x) in pairs.iter().enumerate() {
+            for y in &pairs[..i] {
+                assert_ne!(
+                    Pcg32::from_str_pair(x.0, x.1),
+                    Pcg32::from_str_pair(y.0, y.1)
+                );
+            }
+        }
+    }
+
+    /// Tests that `gen_seed` matches `fill_bytes` on an equal generator.
+    #[test]
+    fn gen_seed() {
+        let mut g = Pcg32::new(0x6a09_e667_bb67_ae85, 0x3c6e_f372_a54f_f53a);
+        let mut h = g.clone();
+
+        let seed: [u8; 32] = g.gen_seed();
+        let mut buf = [0; 32];
+        h.fill_bytes(&mut buf);
+        assert_eq!(seed, buf);
+        assert_eq!(g, h);
+
+        let seed: [u8; 7] = g.gen_seed();
+        let mut buf = [0; 7];
+        h.fill_bytes(&mut buf);
+        assert_eq!(seed, buf);
+        assert_eq!(g, h);
+
+        let _: [u8; 0] = g.gen_seed();
+        assert_eq!(g, h);
+    }
+
+    /// Tests that `from_time` yields different generators at different times.
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_time() {
+        let a = Pcg32::from_time();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let b = Pcg32::from_time();
+        assert_ne!(a, b);
+    }
+
+    /// Tests that `from_entropy` yields different generators on successive calls.
+    #[cfg(feature = "getrandom")]
+    #[test]
+    fn from_entropy() {
+        assert_ne!(Pcg32::from_entropy(), Pcg32::from_entropy());
+    }
+}
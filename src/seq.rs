@@ -0,0 +1,598 @@
+//! Operations on slices and sequences.
+
+use crate::Pcg32;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+impl Pcg32 {
+    /// Shuffles a slice in place using the Fisher–Yates algorithm.
+    ///
+    /// Each swap index is drawn without modulo bias, so every permutation is equally likely.
+    /// A slice of `n` elements consumes one step per index in most cases while the index range
+    /// fits in `u32`, falling back to two steps per index beyond that, and empty or
+    /// single-element slices consume nothing. For example, `[0, 1, 2, 3]` is shuffled into
+    /// `[0, 3, 1, 2]` by the generator seeded with `Pcg32::new(42, 54)`.
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            slice.swap(i, self.gen_index(i + 1));
+        }
+    }
+
+    /// Chooses an element of `slice` uniformly at random, returning `None` if it is empty.
+    pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        if slice.is_empty() {
+            None
+        } else {
+            Some(&slice[self.gen_index(slice.len())])
+        }
+    }
+
+    /// Chooses an element of `slice` uniformly at random and returns a copy of it, or `None` if
+    /// the slice is empty.
+    ///
+    /// This is equivalent to [`choose`](Self::choose) but frees the caller from the lifetime of
+    /// the returned reference.
+    pub fn choose_copied<T: Copy>(&mut self, slice: &[T]) -> Option<T> {
+        self.choose(slice).copied()
+    }
+
+    /// Generates a bitmask with exactly `k` of the low `n` bits set, chosen uniformly among all
+    /// `k`-subsets of `n` positions.
+    ///
+    /// The positions are picked by selection sampling (Knuth's Algorithm S), consuming at most
+    /// one step per candidate bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k > n` or `n > 64`.
+    pub fn gen_k_subset_mask(&mut self, n: u32, k: u32) -> u64 {
+        assert!(n <= 64, "n must not exceed 64");
+        assert!(k <= n, "k must not exceed n");
+        let mut mask = 0;
+        let mut needed = k;
+        for i in 0..n {
+            if needed == 0 {
+                break;
+            }
+            if self.bounded_u32(n - i) < needed {
+                mask |= 1 << i;
+                needed -= 1;
+            }
+        }
+        mask
+    }
+
+    /// Chooses an element of `items` at random, with probabilities proportional to the
+    /// corresponding `weights`.
+    ///
+    /// The weights are summed as `u128`, so the total may exceed `u64::MAX` without overflow or
+    /// loss of precision. Returns `None` if the lengths of `items` and `weights` differ or if
+    /// all weights are zero.
+    pub fn choose_weighted_u64<'a, T>(&mut self, items: &'a [T], weights: &[u64]) -> Option<&'a T> {
+        if items.len() != weights.len() {
+            return None;
+        }
+        let total: u128 = weights.iter().map(|&w| w as u128).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut x = self.bounded_u128(total);
+        for (item, &w) in items.iter().zip(weights) {
+            if x < w as u128 {
+                return Some(item);
+            }
+            x -= w as u128;
+        }
+        unreachable!()
+    }
+
+    /// Fills `out` with references to elements chosen uniformly at random from `slice`, with
+    /// replacement.
+    ///
+    /// The same element may be chosen multiple times, as in bootstrap resampling. Nothing is
+    /// drawn if `out` is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slice` is empty while `out` is not.
+    pub fn sample_with_replacement<'a, T>(&mut self, slice: &'a [T], out: &mut [&'a T]) {
+        if out.is_empty() {
+            return;
+        }
+        assert!(!slice.is_empty(), "cannot sample from an empty slice");
+        for e in out {
+            *e = &slice[self.gen_index(slice.len())];
+        }
+    }
+
+    /// Returns an iterator over a random permutation of `0..n`.
+    ///
+    /// The permutation is shuffled into a buffer up front and then drained lazily, so that it
+    /// composes with iterator adapters.
+    #[cfg(feature = "alloc")]
+    pub fn shuffled_range(&mut self, n: usize) -> impl Iterator<Item = usize> {
+        let mut v: Vec<usize> = (0..n).collect();
+        self.shuffle(&mut v);
+        v.into_iter()
+    }
+
+    /// Generates a random permutation of `0..n` that decomposes into exactly `cycles` cycles.
+    ///
+    /// The elements are shuffled and cut at `cycles - 1` random gaps into nonempty segments, each
+    /// of which is closed into a cycle. The returned vector maps each element `i` to its image
+    /// `perm[i]`. Note that the permutations are not uniformly distributed over those with the
+    /// given number of cycles, because the lengths of the cycles are drawn as a uniform
+    /// composition of `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cycles > n`, or if `cycles` is zero while `n` is not.
+    #[cfg(feature = "alloc")]
+    pub fn permutation_with_cycles(&mut self, n: usize, cycles: usize) -> Vec<usize> {
+        assert!(cycles <= n, "cycles must not exceed n");
+        assert!(
+            cycles > 0 || n == 0,
+            "cycles must be nonzero for nonempty permutations"
+        );
+        let mut perm: Vec<usize> = (0..n).collect();
+        if n == 0 {
+            return perm;
+        }
+        let order: Vec<usize> = self.shuffled_range(n).collect();
+        let cuts = self.gen_schedule(n - 1, cycles - 1);
+        let mut head = 0;
+        for i in 0..n {
+            if i == n - 1 || cuts[i] {
+                perm[order[i]] = order[head];
+                head = i + 1;
+            } else {
+                perm[order[i]] = order[i + 1];
+            }
+        }
+        perm
+    }
+
+    /// Returns a copy of `slice` in which each element is kept independently with probability
+    /// `p`, preserving the original order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in `[0, 1]`.
+    #[cfg(feature = "alloc")]
+    pub fn gen_subsequence<T: Clone>(&mut self, slice: &[T], p: f64) -> Vec<T> {
+        assert!((0.0..=1.0).contains(&p), "p must be in [0, 1]");
+        slice.iter().filter(|_| self.gen_bool(p)).cloned().collect()
+    }
+
+    /// Splits `n` into positive parts that sum to `n`, returned in descending order.
+    ///
+    /// The parts are obtained by the stars-and-bars method: each of the `n - 1` gaps between `n`
+    /// units is cut independently with probability one half, which picks a composition of `n`
+    /// uniformly at random, and the resulting parts are then sorted. Note that the result is
+    /// therefore not uniformly distributed over the integer partitions of `n`. Returns an empty
+    /// vector if `n` is zero.
+    #[cfg(feature = "alloc")]
+    pub fn gen_partition(&mut self, n: u32) -> Vec<u32> {
+        let mut parts = Vec::new();
+        if n == 0 {
+            return parts;
+        }
+        let (mut part, mut bits) = (1, 0);
+        for gap in 0..n - 1 {
+            if gap % 32 == 0 {
+                bits = self.generate();
+            }
+            if bits & 1 != 0 {
+                parts.push(part);
+                part = 0;
+            }
+            part += 1;
+            bits >>= 1;
+        }
+        parts.push(part);
+        parts.sort_unstable_by(|a, b| b.cmp(a));
+        parts
+    }
+
+    /// Assigns each of `items` items to one of `buckets` buckets at random, such that bucket
+    /// sizes differ by at most one.
+    ///
+    /// The items are shuffled and then dealt out to the buckets in turn, so that every balanced
+    /// assignment is equally likely. The returned vector maps each item to its bucket index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buckets` is zero.
+    #[cfg(feature = "alloc")]
+    pub fn balanced_partition(&mut self, items: usize, buckets: usize) -> Vec<usize> {
+        assert!(buckets > 0, "buckets must be nonzero");
+        let mut assignment: Vec<usize> = (0..items).map(|i| i % buckets).collect();
+        self.shuffle(&mut assignment);
+        assignment
+    }
+
+    /// Generates a schedule of `slots` slots of which exactly `active` are `true`, placed
+    /// uniformly at random.
+    ///
+    /// As with [`gen_k_subset_mask`](Self::gen_k_subset_mask), the active slots are picked by
+    /// selection sampling, so that every `active`-subset of slots is equally likely.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `active > slots`.
+    #[cfg(feature = "alloc")]
+    pub fn gen_schedule(&mut self, slots: usize, active: usize) -> Vec<bool> {
+        assert!(active <= slots, "active must not exceed slots");
+        let mut needed = active;
+        (0..slots)
+            .map(|i| {
+                let pick = needed > 0 && self.gen_index(slots - i) < needed;
+                needed -= pick as usize;
+                pick
+            })
+            .collect()
+    }
+}
+
+/// Shuffles a slice in place using a temporary generator seeded from `key`.
+///
+/// The same `key` always yields the same permutation for slices of the same length, so callers
+/// get reproducible shuffles without having to manage generator state.
+#[cfg(feature = "alloc")]
+pub fn shuffle_keyed<T>(slice: &mut [T], key: u64) {
+    Pcg32::seed_from_u64(key).shuffle(slice);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    /// Tests that `shuffle` yields a pinned permutation and draws nothing for short slices.
+    #[test]
+    fn shuffle() {
+        let mut g = Pcg32::new(42, 54);
+        let mut deck = [0, 1, 2, 3];
+        g.shuffle(&mut deck);
+        assert_eq!(deck, [0, 3, 1, 2]);
+
+        let h = g.clone();
+        g.shuffle(&mut [0u8; 0]);
+        g.shuffle(&mut [7]);
+        assert_eq!(g, h);
+
+        let mut counts = [[0u32; 4]; 4];
+        for _ in 0..40_000 {
+            let mut deck = [0, 1, 2, 3];
+            g.shuffle(&mut deck);
+            let mut sorted = deck;
+            sorted.sort_unstable();
+            assert_eq!(sorted, [0, 1, 2, 3]);
+            for (pos, &card) in deck.iter().enumerate() {
+                counts[card][pos] += 1;
+            }
+        }
+        assert!(counts
+            .iter()
+            .flatten()
+            .all(|&c| (c as i32 - 10_000).abs() < 400));
+    }
+
+    /// Tests that `choose` picks every element with equal probability.
+    #[test]
+    fn choose() {
+        let mut g = Pcg32::new(0x6ca6_351e_0030_0000, 0x1429_2967_0a0e_6e70);
+        let slice = [3, 1, 4, 1, 5, 9, 2, 6];
+        let n = 80_000;
+        let mut freq = [0u32; 8];
+        for _ in 0..n {
+            let e = g.choose(&slice).unwrap();
+            freq[slice.iter().position(|x| core::ptr::eq(x, e)).unwrap()] += 1;
+        }
+        for f in freq {
+            assert!((f as f64 / n as f64 - 0.125).abs() < 0.01);
+        }
+        assert_eq!(g.choose::<u32>(&[]), None);
+    }
+
+    /// Tests that `choose_copied` returns the same element as `choose` on an equal generator.
+    #[test]
+    fn choose_copied() {
+        let mut g = Pcg32::new(0x27b7_0a85_46d2_2ffc, 0x2e1b_2138_5c26_c926);
+        let mut h = g.clone();
+        let slice = ['p', 'c', 'g', '3', '2'];
+        for len in 0..=slice.len() {
+            for _ in 0..100 {
+                assert_eq!(
+                    g.choose_copied(&slice[..len]),
+                    h.choose(&slice[..len]).copied()
+                );
+            }
+        }
+        assert_eq!(g, h);
+    }
+
+    /// Tests that `gen_k_subset_mask` sets exactly `k` bits with uniform per-bit frequencies.
+    #[test]
+    fn gen_k_subset_mask() {
+        let mut g = Pcg32::new(0x510e_527f_ade6_82d1, 0x9b05_688c_2b3e_6c1f);
+        for (n, k) in [
+            (0, 0),
+            (1, 0),
+            (1, 1),
+            (10, 3),
+            (33, 16),
+            (64, 0),
+            (64, 5),
+            (64, 64),
+        ] {
+            let trials = 20_000;
+            let mut freq = [0u32; 64];
+            for _ in 0..trials {
+                let mask = g.gen_k_subset_mask(n, k);
+                assert_eq!(mask.count_ones(), k);
+                assert_eq!(mask.checked_shr(n).unwrap_or(0), 0);
+                for (i, f) in freq.iter_mut().enumerate() {
+                    *f += (mask >> i) as u32 & 1;
+                }
+            }
+            let expected = if n == 0 { 0.0 } else { k as f64 / n as f64 };
+            for &f in &freq[..n as usize] {
+                assert!((f as f64 / trials as f64 - expected).abs() < 0.02);
+            }
+        }
+    }
+
+    /// Tests that `gen_k_subset_mask` rejects `k > n`.
+    #[test]
+    #[should_panic]
+    fn gen_k_subset_mask_k_exceeds_n() {
+        Pcg32::default().gen_k_subset_mask(4, 5);
+    }
+
+    /// Tests that `gen_k_subset_mask` rejects `n > 64`.
+    #[test]
+    #[should_panic]
+    fn gen_k_subset_mask_n_exceeds_64() {
+        Pcg32::default().gen_k_subset_mask(65, 1);
+    }
+
+    /// Tests that `choose_weighted_u64` handles totals above `u64::MAX` correctly.
+    #[test]
+    fn choose_weighted_u64() {
+        let mut g = Pcg32::new(0x2748_774c_df8e_eb99, 0x34b0_bcb5_e19b_48a8);
+        let items = ['a', 'b', 'c', 'd', 'e'];
+
+        assert_eq!(g.choose_weighted_u64(&items, &[1, 2, 3]), None);
+        assert_eq!(g.choose_weighted_u64(&items, &[0; 5]), None);
+        assert_eq!(g.choose_weighted_u64::<char>(&[], &[]), None);
+        assert_eq!(g.choose_weighted_u64(&items, &[0, 0, 7, 0, 0]), Some(&'c'));
+
+        let weights = [u64::MAX, u64::MAX / 2, 0, u64::MAX, 1];
+        let n = 100_000;
+        let mut freq = [0u32; 5];
+        for _ in 0..n {
+            let c = g.choose_weighted_u64(&items, &weights).unwrap();
+            freq[items.iter().position(|e| e == c).unwrap()] += 1;
+        }
+        let total: f64 = weights.iter().map(|&w| w as f64).sum();
+        for (f, w) in freq.into_iter().zip(weights) {
+            assert!((f as f64 / n as f64 - w as f64 / total).abs() < 0.01);
+        }
+        assert_eq!(freq[2], 0);
+    }
+
+    /// Tests that `shuffled_range` yields a deterministic permutation.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn shuffled_range() {
+        use alloc::vec::Vec;
+
+        let g = Pcg32::new(0x0fc1_9dc6_8b8c_d5b5, 0x240c_a1cc_77ac_9c65);
+        for n in [0, 1, 2, 10, 1000] {
+            let v: Vec<usize> = g.clone().shuffled_range(n).collect();
+            assert_eq!(v, g.clone().shuffled_range(n).collect::<Vec<_>>());
+            let mut sorted = v.clone();
+            sorted.sort_unstable();
+            assert!(sorted.into_iter().eq(0..n));
+            if n >= 10 {
+                assert!(!v.into_iter().eq(0..n));
+            }
+        }
+    }
+
+    /// Tests that `permutation_with_cycles` returns permutations with the requested cycles.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn permutation_with_cycles() {
+        use alloc::vec;
+
+        let mut g = Pcg32::new(0x0d95_748f_728e_b658, 0xa458_fea3_f493_3d7e);
+        for (n, cycles) in [(0, 0), (1, 1), (5, 1), (5, 5), (10, 3), (200, 17)] {
+            for _ in 0..50 {
+                let perm = g.permutation_with_cycles(n, cycles);
+                let mut sorted = perm.clone();
+                sorted.sort_unstable();
+                assert!(sorted.into_iter().eq(0..n));
+
+                let mut visited = vec![false; n];
+                let mut count = 0;
+                for start in 0..n {
+                    if !visited[start] {
+                        count += 1;
+                        let mut i = start;
+                        while !visited[i] {
+                            visited[i] = true;
+                            i = perm[i];
+                        }
+                    }
+                }
+                assert_eq!(count, cycles);
+            }
+        }
+    }
+
+    /// Tests that `permutation_with_cycles` rejects more cycles than elements.
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic]
+    fn permutation_with_cycles_too_many() {
+        Pcg32::default().permutation_with_cycles(3, 4);
+    }
+
+    /// Tests that `gen_subsequence` preserves order and keeps the expected fraction.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn gen_subsequence() {
+        let mut g = Pcg32::new(0x4a74_84aa_6ea6_e483, 0x5cb0_a9dc_bd41_fbd4);
+        let slice: [u32; 1000] = core::array::from_fn(|i| i as u32);
+        for p in [0.0, 0.1, 0.5, 0.9, 1.0] {
+            let mut total = 0;
+            for _ in 0..100 {
+                let sub = g.gen_subsequence(&slice, p);
+                assert!(sub.windows(2).all(|w| w[0] < w[1]));
+                total += sub.len();
+            }
+            assert!((total as f64 / 100.0 - p * slice.len() as f64).abs() < 5.0);
+        }
+        assert!(g.gen_subsequence::<u32>(&[], 0.5).is_empty());
+    }
+
+    /// Tests that `gen_partition` returns positive parts summing to `n` in descending order.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn gen_partition() {
+        let mut g = Pcg32::new(0x2e1b_2138_4d2c_6dfc, 0x5ac4_2aed_5338_0d13);
+        for n in [1, 2, 5, 31, 32, 33, 100, 10_000] {
+            let mut total_parts = 0;
+            for _ in 0..100 {
+                let parts = g.gen_partition(n);
+                assert_eq!(parts.iter().sum::<u32>(), n);
+                assert!(parts.iter().all(|&p| p > 0));
+                assert!(parts.windows(2).all(|w| w[0] >= w[1]));
+                total_parts += parts.len();
+            }
+            // each of the `n - 1` gaps is cut with probability one half
+            let expected = 1.0 + (n - 1) as f64 / 2.0;
+            assert!((total_parts as f64 / 100.0 - expected).abs() < 0.05 * expected.max(10.0));
+        }
+        assert!(g.gen_partition(0).is_empty());
+        assert_eq!(g.gen_partition(1), [1]);
+    }
+
+    /// Tests that `balanced_partition` yields balanced and deterministic assignments.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn balanced_partition() {
+        let g = Pcg32::new(0x9216_d5d9_8979_fb1b, 0xd131_0ba6_98df_b5ac);
+        for (items, buckets) in [(0, 3), (1, 1), (5, 8), (100, 7), (1000, 10)] {
+            let assignment = g.clone().balanced_partition(items, buckets);
+            assert_eq!(assignment, g.clone().balanced_partition(items, buckets));
+            assert_eq!(assignment.len(), items);
+
+            let mut sizes = [0usize; 10];
+            for &b in &assignment {
+                sizes[b] += 1;
+            }
+            let sizes = &sizes[..buckets];
+            let (min, max) = (sizes.iter().min().unwrap(), sizes.iter().max().unwrap());
+            assert!(max - min <= 1);
+            if items >= 100 {
+                assert!(assignment.iter().zip(0..).any(|(&b, i)| b != i % buckets));
+            }
+        }
+    }
+
+    /// Tests that `gen_schedule` activates exactly the requested number of uniform slots.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn gen_schedule() {
+        let mut g = Pcg32::new(0x5851_f42d_4c95_7f2d, 0x2545_f491_4f6c_dd1d);
+        for (slots, active) in [(0, 0), (5, 0), (5, 5), (20, 7), (1000, 1)] {
+            for _ in 0..100 {
+                let schedule = g.gen_schedule(slots, active);
+                assert_eq!(schedule.len(), slots);
+                assert_eq!(schedule.iter().filter(|&&b| b).count(), active);
+            }
+        }
+
+        let n = 20_000;
+        let mut freq = [0u32; 10];
+        for _ in 0..n {
+            for (f, b) in freq.iter_mut().zip(g.gen_schedule(10, 3)) {
+                *f += b as u32;
+            }
+        }
+        assert!(freq
+            .iter()
+            .all(|&f| (f as f64 / n as f64 - 0.3).abs() < 0.02));
+
+        let a = Pcg32::new(1, 2).gen_schedule(64, 8);
+        let b = Pcg32::new(3, 4).gen_schedule(64, 8);
+        assert_eq!(a, Pcg32::new(1, 2).gen_schedule(64, 8));
+        assert_ne!(a, b);
+    }
+
+    /// Tests that `gen_schedule` rejects more active slots than slots.
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic]
+    fn gen_schedule_active_exceeds_slots() {
+        Pcg32::default().gen_schedule(3, 4);
+    }
+
+    /// Tests that `sample_with_replacement` fills the output with elements of the slice.
+    #[test]
+    fn sample_with_replacement() {
+        let mut g = Pcg32::new(0x2de9_2c6f_592b_0275, 0x4a74_84aa_6ea6_e483);
+        let slice = [10, 20, 30, 40, 50, 60, 70];
+        let n = 70_000;
+        let mut freq = [0u32; 7];
+        let mut out = [&0; 100];
+        for _ in 0..n / out.len() {
+            g.sample_with_replacement(&slice, &mut out);
+            for &e in &out {
+                assert!(slice.as_ptr_range().contains(&(e as *const _)));
+                freq[slice.iter().position(|x| core::ptr::eq(x, e)).unwrap()] += 1;
+            }
+        }
+        for f in freq {
+            assert!((f as f64 / n as f64 - 1.0 / 7.0).abs() < 0.01);
+        }
+
+        let h = g.clone();
+        g.sample_with_replacement::<u32>(&[], &mut []);
+        g.sample_with_replacement(&slice, &mut []);
+        assert_eq!(g, h);
+    }
+
+    /// Tests that `sample_with_replacement` rejects an empty slice with a nonempty output.
+    #[test]
+    #[should_panic]
+    fn sample_with_replacement_empty_slice() {
+        Pcg32::default().sample_with_replacement::<u32>(&[], &mut [&0]);
+    }
+
+    /// Tests that `shuffle_keyed` is a deterministic function of the key.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn shuffle_keyed() {
+        use super::shuffle_keyed;
+
+        let identity: [u32; 32] = core::array::from_fn(|i| i as u32);
+        let (mut a, mut b, mut c) = (identity, identity, identity);
+        shuffle_keyed(&mut a, 42);
+        shuffle_keyed(&mut b, 42);
+        shuffle_keyed(&mut c, 43);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a, identity);
+
+        let mut sorted = a;
+        sorted.sort_unstable();
+        assert_eq!(sorted, identity);
+
+        let mut empty: [u32; 0] = [];
+        shuffle_keyed(&mut empty, 42);
+    }
+}
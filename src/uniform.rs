@@ -0,0 +1,544 @@
+//! Uniformly distributed values other than raw 32-bit integers.
+
+use crate::Pcg32;
+
+use core::ops::{Range, RangeInclusive};
+
+impl Pcg32 {
+    /// Generates a pseudorandom uniformly distributed `f64` value in `[0, 1)`.
+    ///
+    /// The top 53 bits of [`next_u64`](Self::next_u64) are divided by `2^53`, so the result is
+    /// uniformly distributed over the `2^53` multiples of `2^-53` in the interval. This consumes
+    /// two steps of the underlying stream. `0.0` is attainable, whereas `1.0` is never returned.
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Generates a pseudorandom uniformly distributed `f32` value in `[0, 1)`.
+    ///
+    /// The top 24 bits of a single [`generate`](Self::generate) call are divided by `2^24`, so
+    /// the result is uniformly distributed over the `2^24` multiples of `2^-24` in the interval
+    /// at the cost of one step. `1.0` is never returned.
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        (self.generate() >> 8) as f32 * (1.0 / (1u32 << 24) as f32)
+    }
+
+    /// Generates a pseudorandom uniformly distributed `f64` value in the open interval `(0, 1)`
+    /// by rejecting zero.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn next_f64_nonzero(&mut self) -> f64 {
+        loop {
+            let u = self.next_f64();
+            if u != 0.0 {
+                return u;
+            }
+        }
+    }
+
+    /// Generates a pseudorandom uniformly distributed integer in `[0, n)` using Lemire's
+    /// nearly-divisionless method. `n` must be nonzero.
+    pub(crate) fn bounded_u32(&mut self, n: u32) -> u32 {
+        debug_assert!(n > 0);
+        let mut m = self.generate() as u64 * n as u64;
+        if (m as u32) < n {
+            let threshold = n.wrapping_neg() % n;
+            while (m as u32) < threshold {
+                m = self.generate() as u64 * n as u64;
+            }
+        }
+        (m >> 32) as u32
+    }
+
+    /// Generates a pseudorandom uniformly distributed integer in `[0, n)` using Lemire's
+    /// nearly-divisionless method. `n` must be nonzero.
+    pub(crate) fn bounded_u64(&mut self, n: u64) -> u64 {
+        debug_assert!(n > 0);
+        let mut m = self.next_u64() as u128 * n as u128;
+        if (m as u64) < n {
+            let threshold = n.wrapping_neg() % n;
+            while (m as u64) < threshold {
+                m = self.next_u64() as u128 * n as u128;
+            }
+        }
+        (m >> 64) as u64
+    }
+
+    /// Generates a pseudorandom uniformly distributed integer in `[0, n)` by masked rejection
+    /// sampling, falling back to [`bounded_u64`](Self::bounded_u64) whenever `n` fits in `u64`.
+    /// `n` must be nonzero.
+    pub(crate) fn bounded_u128(&mut self, n: u128) -> u128 {
+        debug_assert!(n > 0);
+        if let Ok(n) = u64::try_from(n) {
+            return self.bounded_u64(n) as u128;
+        }
+        let mask = u128::MAX >> (n - 1).leading_zeros();
+        loop {
+            let x = ((self.next_u64() as u128) << 64 | self.next_u64() as u128) & mask;
+            if x < n {
+                return x;
+            }
+        }
+    }
+
+    /// Generates a pseudorandom uniformly distributed index in `[0, n)`, consuming a single
+    /// 32-bit step per attempt whenever `n` fits in `u32`. `n` must be nonzero.
+    pub(crate) fn gen_index(&mut self, n: usize) -> usize {
+        match u32::try_from(n) {
+            Ok(n) => self.bounded_u32(n) as usize,
+            Err(_) => self.bounded_u64(n as u64) as usize,
+        }
+    }
+
+    /// Generates a pseudorandom integer uniformly distributed in `range` without modulo bias.
+    ///
+    /// This uses Lemire's nearly-divisionless method: each attempt consumes one step, and an
+    /// attempt is rejected with probability `(2^32 % n) / 2^32 < n / 2^32`, where `n` is the
+    /// width of the range. Hence, a single step is consumed in most cases, and the number of
+    /// steps is fully determined by the generator state. Use
+    /// [`gen_range_inclusive_u32`](Self::gen_range_inclusive_u32) to cover all `2^32` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn gen_range_u32(&mut self, range: Range<u32>) -> u32 {
+        assert!(range.start < range.end, "cannot sample from an empty range");
+        range.start + self.bounded_u32(range.end - range.start)
+    }
+
+    /// Generates a pseudorandom integer uniformly distributed in the inclusive `range` without
+    /// modulo bias.
+    ///
+    /// This shares the rejection method of [`gen_range_u32`](Self::gen_range_u32), except that
+    /// a range covering all `2^32` values, whose width overflows `u32`, is served by a single
+    /// raw [`generate`](Self::generate) call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range.start() > range.end()`.
+    pub fn gen_range_inclusive_u32(&mut self, range: RangeInclusive<u32>) -> u32 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "cannot sample from an empty range");
+        match (end - start).checked_add(1) {
+            Some(n) => start + self.bounded_u32(n),
+            None => self.generate(),
+        }
+    }
+
+    /// Generates a pseudorandom integer uniformly distributed in `range` without modulo bias.
+    ///
+    /// This applies Lemire's method to the 128-bit product of [`next_u64`](Self::next_u64) and
+    /// the width `n` of the range. Each attempt consumes two steps and is rejected with
+    /// probability `(2^64 % n) / 2^64 < n / 2^64`. The number of attempts is not bounded, but
+    /// the rejection probability stays below one half even for the widest range, so at most
+    /// four steps are consumed on average.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn gen_range_u64(&mut self, range: Range<u64>) -> u64 {
+        assert!(range.start < range.end, "cannot sample from an empty range");
+        range.start + self.bounded_u64(range.end - range.start)
+    }
+
+    /// Returns `true` with probability `p`.
+    ///
+    /// This compares a single [`next_f64`](Self::next_f64) draw against `p`, so it consumes two
+    /// steps regardless of `p`. Consequently, `p == 0.0` always returns `false` and `p == 1.0`
+    /// always returns `true`, while still advancing the generator as any other call does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in `[0, 1]`, including if it is NaN.
+    pub fn gen_bool(&mut self, p: f64) -> bool {
+        assert!((0.0..=1.0).contains(&p), "p must be in [0, 1]");
+        self.next_f64() < p
+    }
+
+    /// Returns `true` with probability exactly `numerator / denominator`.
+    ///
+    /// Unlike [`gen_bool`](Self::gen_bool), this involves no floating-point rounding: it draws
+    /// an integer uniformly from `0..denominator` by the method of
+    /// [`gen_range_u32`](Self::gen_range_u32) and compares it against `numerator`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is zero or `numerator > denominator`.
+    pub fn gen_ratio(&mut self, numerator: u32, denominator: u32) -> bool {
+        assert!(denominator > 0, "denominator must be nonzero");
+        assert!(
+            numerator <= denominator,
+            "numerator must not exceed denominator"
+        );
+        self.bounded_u32(denominator) < numerator
+    }
+
+    /// Generates a `u32` whose low `count` bits are each set independently with probability `p`,
+    /// leaving the bits above `count` cleared.
+    ///
+    /// For `p == 0.5` this consumes a single step and masks the output; otherwise each bit is
+    /// drawn separately.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count > 32` or if `p` is not in `[0, 1]`.
+    pub fn gen_bool_packed(&mut self, count: u32, p: f64) -> u32 {
+        assert!(count <= 32, "count must not exceed 32");
+        assert!((0.0..=1.0).contains(&p), "p must be in [0, 1]");
+        if p == 0.5 {
+            self.generate() & u32::MAX.checked_shr(32 - count).unwrap_or(0)
+        } else {
+            (0..count).fold(0, |acc, i| acc | ((self.next_f64() < p) as u32) << i)
+        }
+    }
+
+    /// Generates a pseudorandom uniformly distributed `f64` value in `range`.
+    ///
+    /// The result is computed as `start + next_f64() * (end - start)`. Since this product is
+    /// rounded, values are not exactly equidistributed over the representable floats of the
+    /// range, and rounding could carry the result up to `end`, in which case it is redrawn so
+    /// that `end` is never returned. If `end - start` overflows to infinity, the result is
+    /// interpolated between the bounds instead, so finite bounds never yield NaN or infinities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either bound is not finite or `start >= end`.
+    pub fn gen_range_f64(&mut self, range: Range<f64>) -> f64 {
+        let Range { start, end } = range;
+        assert!(
+            start.is_finite() && end.is_finite() && start < end,
+            "range bounds must be finite and satisfy start < end"
+        );
+        self.uniform_f64(start, end)
+    }
+
+    /// Generates a pseudorandom uniformly distributed `f64` value in `[low, high)`, or returns
+    /// `None` if either bound is not finite or `low >= high`.
+    ///
+    /// This is a panic-free variant of [`gen_range_f64`](Self::gen_range_f64) for library code
+    /// that receives ranges from untrusted input.
+    pub fn gen_range_f64_checked(&mut self, range: Range<f64>) -> Option<f64> {
+        let Range { start, end } = range;
+        if start.is_finite() && end.is_finite() && start < end {
+            Some(self.uniform_f64(start, end))
+        } else {
+            None
+        }
+    }
+
+    /// Generates a pseudorandom uniformly distributed `f64` value in `[low, high)`.
+    ///
+    /// Both bounds must be finite and `low < high`. The result is redrawn in the rare case that
+    /// rounding carries it up to `high`.
+    pub(crate) fn uniform_f64(&mut self, low: f64, high: f64) -> f64 {
+        debug_assert!(low.is_finite() && high.is_finite() && low < high);
+        let scale = high - low;
+        loop {
+            let u = self.next_f64();
+            let x = if scale.is_finite() {
+                low + scale * u
+            } else {
+                // `high - low` overflows only if the bounds have opposite signs, in which case
+                // this interpolation form stays finite
+                low * (1.0 - u) + high * u
+            };
+            if x < high {
+                return x;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Pcg32;
+
+    /// Tests that `next_f64` returns uniform values in `[0, 1)` from two steps each.
+    #[test]
+    fn next_f64() {
+        let mut g = Pcg32::new(0x1f83_d9ab_fb41_bd6b, 0x5be0_cd19_137e_2179);
+        let n = 100_000;
+        let mut buckets = [0u32; 16];
+        for _ in 0..n {
+            let mut h = g.clone();
+            let x = g.next_f64();
+            assert!((0.0..1.0).contains(&x));
+            assert_eq!(x, (h.next_u64() >> 11) as f64 / (1u64 << 53) as f64);
+            assert_eq!(g, h);
+            buckets[(x * 16.0) as usize] += 1;
+        }
+        for b in buckets {
+            assert!((b as f64 / n as f64 - 1.0 / 16.0).abs() < 0.005);
+        }
+
+        // the zero state yields two zero outputs
+        assert_eq!(Pcg32 { state: 0, inc: 1 }.next_f64(), 0.0);
+    }
+
+    /// Tests that `next_f32` returns uniform values in `[0, 1)` from one step each.
+    #[test]
+    fn next_f32() {
+        let mut g = Pcg32::new(0x0fc1_9dc6_8b8c_d5b5, 0x9bdc_06a7_25c7_1235);
+        let n = 100_000;
+        let (mut min, mut max) = (f32::INFINITY, f32::NEG_INFINITY);
+        let mut buckets = [0u32; 16];
+        for _ in 0..n {
+            let mut h = g.clone();
+            let x = g.next_f32();
+            h.generate();
+            assert_eq!(g, h);
+            min = min.min(x);
+            max = max.max(x);
+            buckets[(x * 16.0) as usize] += 1;
+        }
+        assert!(min >= 0.0 && max < 1.0);
+        for b in buckets {
+            assert!((b as f64 / n as f64 - 1.0 / 16.0).abs() < 0.005);
+        }
+
+        // the largest output maps to the largest value below one
+        let mut g = Pcg32::new(0, 0);
+        let mut h = g.clone();
+        while h.generate() >> 8 != 0xff_ffff {
+            g.generate();
+        }
+        assert_eq!(g.next_f32(), 1.0 - f32::EPSILON / 2.0);
+    }
+
+    /// Tests that `gen_bool` returns `true` with the expected frequency and fixed draw count.
+    #[test]
+    fn gen_bool() {
+        let mut g = Pcg32::new(0xd807_aa98_a303_0242, 0x1283_5b01_4570_6fbe);
+        let h = g.clone();
+        for p in [0.0, 0.5, 1.0] {
+            let n = 100_000;
+            let mut trues = 0;
+            for _ in 0..n {
+                let mut before = g.clone();
+                trues += g.gen_bool(p) as u32;
+                before.next_u64();
+                assert_eq!(g, before);
+            }
+            assert!((trues as f64 / n as f64 - p).abs() < 0.01);
+            if p == 0.0 || p == 1.0 {
+                assert_eq!(trues as f64, p * n as f64);
+            }
+        }
+
+        let (mut a, mut b) = (h.clone(), h);
+        for _ in 0..100 {
+            assert_eq!(a.gen_bool(0.3), b.gen_bool(0.3));
+        }
+    }
+
+    /// Tests that `gen_bool` rejects NaN.
+    #[test]
+    #[should_panic]
+    fn gen_bool_nan() {
+        Pcg32::default().gen_bool(f64::NAN);
+    }
+
+    /// Tests that `gen_ratio` returns `true` with the exact rational probability.
+    #[test]
+    fn gen_ratio() {
+        let mut g = Pcg32::new(0x2431_85be_4ee4_b28c, 0x550c_7dc3_d5ff_b4e2);
+        for (numerator, denominator) in [(1, 3), (0, 5), (5, 5), (2, 7), (1 << 31, u32::MAX)] {
+            let n = 150_000;
+            let trues = (0..n)
+                .filter(|_| g.gen_ratio(numerator, denominator))
+                .count();
+            let p = numerator as f64 / denominator as f64;
+            assert!((trues as f64 / n as f64 - p).abs() < 0.005);
+            if numerator == 0 || numerator == denominator {
+                assert_eq!(trues as f64, p * n as f64);
+            }
+        }
+    }
+
+    /// Tests that `gen_ratio` rejects a numerator greater than the denominator.
+    #[test]
+    #[should_panic]
+    fn gen_ratio_improper() {
+        Pcg32::default().gen_ratio(4, 3);
+    }
+
+    /// Tests that `gen_bool_packed` sets the requested bits with the expected frequency.
+    #[test]
+    fn gen_bool_packed() {
+        let mut g = Pcg32::new(0x9216_d5d9_8979_fb1b, 0xd131_0ba6_98df_b5ac);
+        for count in [0, 1, 7, 31, 32] {
+            for p in [0.0, 0.2, 0.5, 0.9, 1.0] {
+                let n = 20_000;
+                let mut freq = [0u32; 32];
+                for _ in 0..n {
+                    let x = g.gen_bool_packed(count, p);
+                    assert_eq!(x.checked_shr(count).unwrap_or(0), 0);
+                    for (i, f) in freq.iter_mut().enumerate() {
+                        *f += (x >> i) & 1;
+                    }
+                }
+                for &f in &freq[..count as usize] {
+                    assert!((f as f64 / n as f64 - p).abs() < 0.02);
+                }
+            }
+        }
+    }
+
+    /// Tests that `gen_range_u32` returns every value of small ranges and nothing outside.
+    #[test]
+    fn gen_range_u32() {
+        let mut g = Pcg32::new(0x3f84_d5b5_b547_0917, 0x9216_d5d9_8979_fb1b);
+        for (start, end) in [(0, 1), (0, 3), (10, 17), (u32::MAX - 5, u32::MAX)] {
+            let n = 60_000;
+            let width = (end - start) as usize;
+            let mut freq = [0u32; 7];
+            for _ in 0..n {
+                let x = g.gen_range_u32(start..end);
+                assert!((start..end).contains(&x));
+                freq[(x - start) as usize] += 1;
+            }
+            for &f in &freq[..width] {
+                assert!((f as f64 / n as f64 - 1.0 / width as f64).abs() < 0.01);
+            }
+        }
+
+        let mut high = 0;
+        for _ in 0..1000 {
+            let x = g.gen_range_u32(0..u32::MAX);
+            assert!(x < u32::MAX);
+            high += (x >= 1 << 31) as u32;
+        }
+        assert!((400..600).contains(&high));
+    }
+
+    /// Tests that `gen_range_u32` rejects an empty range.
+    #[test]
+    #[should_panic]
+    fn gen_range_u32_empty() {
+        Pcg32::default().gen_range_u32(5..5);
+    }
+
+    /// Tests that `gen_range_inclusive_u32` handles the full domain and single-value ranges.
+    #[test]
+    fn gen_range_inclusive_u32() {
+        let mut g = Pcg32::new(0xe9b5_dba5_8189_dbbc, 0x3956_c25b_f348_b538);
+
+        let mut h = g.clone();
+        for _ in 0..100 {
+            assert_eq!(g.gen_range_inclusive_u32(0..=u32::MAX), h.generate());
+        }
+
+        for _ in 0..100 {
+            assert_eq!(g.gen_range_inclusive_u32(5..=5), 5);
+            h.generate();
+            assert_eq!(g, h);
+        }
+        assert_eq!(g.gen_range_inclusive_u32(u32::MAX..=u32::MAX), u32::MAX);
+
+        let n = 40_000;
+        let mut freq = [0u32; 4];
+        for _ in 0..n {
+            let x = g.gen_range_inclusive_u32(u32::MAX - 3..=u32::MAX);
+            freq[(x - (u32::MAX - 3)) as usize] += 1;
+        }
+        assert!(freq
+            .iter()
+            .all(|&f| (f as f64 / n as f64 - 0.25).abs() < 0.01));
+    }
+
+    /// Tests that `gen_range_inclusive_u32` rejects an inverted range.
+    #[test]
+    #[should_panic]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn gen_range_inclusive_u32_inverted() {
+        Pcg32::default().gen_range_inclusive_u32(6..=5);
+    }
+
+    /// Tests that `gen_range_u64` returns uniformly distributed values within the range.
+    #[test]
+    fn gen_range_u64() {
+        let mut g = Pcg32::new(0x7137_4491_23ef_65cd, 0xb5c0_fbcf_ec4d_3b2f);
+        for (start, end) in [
+            (0, 10),
+            (1 << 40, (1 << 40) + 10_000_000_000),
+            (0, u64::MAX),
+        ] {
+            let n = 50_000;
+            let width = end - start;
+            let mut buckets = [0u32; 10];
+            for _ in 0..n {
+                let x = g.gen_range_u64(start..end);
+                assert!((start..end).contains(&x));
+                buckets[((x - start) as u128 * 10 / width as u128) as usize] += 1;
+            }
+            for b in buckets {
+                assert!((b as f64 / n as f64 - 0.1).abs() < 0.01);
+            }
+        }
+        assert_eq!(g.gen_range_u64(u64::MAX - 1..u64::MAX), u64::MAX - 1);
+    }
+
+    /// Tests that `gen_range_u64` rejects an empty range.
+    #[test]
+    #[should_panic]
+    fn gen_range_u64_empty() {
+        Pcg32::default().gen_range_u64(7..7);
+    }
+
+    /// Tests that `gen_range_f64` stays within the range, including signed intervals.
+    #[test]
+    fn gen_range_f64() {
+        let mut g = Pcg32::new(0x6c44_198c_4a47_5817, 0x1f83_d9ab_fb41_bd6b);
+        let n = 100_000;
+        let (mut sum, mut negative) = (0.0, 0);
+        for _ in 0..n {
+            let x = g.gen_range_f64(-1.0..1.0);
+            assert!((-1.0..1.0).contains(&x));
+            sum += x;
+            negative += (x < 0.0) as u32;
+        }
+        assert!((sum / n as f64).abs() < 0.01);
+        assert!((negative as f64 / n as f64 - 0.5).abs() < 0.01);
+
+        for _ in 0..10_000 {
+            let x = g.gen_range_f64(-f64::MAX..f64::MAX);
+            assert!(x.is_finite());
+        }
+    }
+
+    /// Tests that `gen_range_f64` rejects a range with an infinite bound.
+    #[test]
+    #[should_panic]
+    fn gen_range_f64_infinite() {
+        Pcg32::default().gen_range_f64(0.0..f64::INFINITY);
+    }
+
+    /// Tests that `gen_range_f64_checked` rejects invalid ranges and honors valid ones.
+    #[test]
+    fn gen_range_f64_checked() {
+        let mut g = Pcg32::new(0x6a09_e667_f3bc_c908, 0xbb67_ae85_84ca_a73b);
+        let h = g.clone();
+
+        for (start, end) in [
+            (f64::NAN, 1.0),
+            (0.0, f64::NAN),
+            (f64::NAN, f64::NAN),
+            (f64::NEG_INFINITY, 0.0),
+            (0.0, f64::INFINITY),
+            (f64::NEG_INFINITY, f64::INFINITY),
+            (1.0, 1.0),
+            (2.0, 1.0),
+        ] {
+            assert_eq!(g.gen_range_f64_checked(start..end), None);
+        }
+        assert_eq!(g, h);
+
+        for (start, end) in [(-1.0, 1.0), (0.5, 0.5000001), (-f64::MAX, f64::MAX)] {
+            for _ in 0..10_000 {
+                let x = g.gen_range_f64_checked(start..end).unwrap();
+                assert!(start <= x && x < end);
+            }
+        }
+    }
+}